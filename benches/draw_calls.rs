@@ -0,0 +1,200 @@
+//! Draw calls and frame times of the sprite renderer with and without batching
+//!
+//! Plays a run without a window and draws every frame into an offscreen framebuffer on a
+//! surfaceless EGL context, once as a single batch and once with a draw call per quad
+//! like the renderer did before the atlas. The draw calls are counted by the renderer.
+//!
+//! Run with `cargo bench --bench draw_calls`. It is skipped where EGL has no device or
+//! doesn't exist at all, like on macOS.
+
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use flappybirb::atlas::AtlasBuilder;
+use flappybirb::background::BackgroundSprite;
+use flappybirb::bird::BirdSprite;
+use flappybirb::config::Config;
+use flappybirb::ground::GroundSprite;
+use flappybirb::pipes::PipesSprite;
+use flappybirb::renderer::{SpriteBatch, SpriteRenderer};
+use flappybirb::ui::Ui;
+use flappybirb::{GameWorld, Render, SIMULATION_STEP, WORLD_SIZE};
+use glium::backend::Context;
+use glium::framebuffer::SimpleFrameBuffer;
+use glium::texture::SrgbTexture2d;
+use glium::Surface;
+
+const FRAMES: u32 = 2_000;
+/// Frames between flaps, keeps the bird in the air without hitting anything
+const FLAP_INTERVAL: u32 = 20;
+
+// glutin has no EGL backend on Apple platforms
+#[cfg(all(any(unix, windows), not(any(target_os = "macos", target_os = "ios"))))]
+use egl::headless_context;
+
+#[cfg(not(all(any(unix, windows), not(any(target_os = "macos", target_os = "ios")))))]
+fn headless_context() -> Result<Rc<Context>, String> {
+    Err("EGL is not available on this platform".to_string())
+}
+
+#[cfg(all(any(unix, windows), not(any(target_os = "macos", target_os = "ios"))))]
+mod egl {
+    use std::ffi::{c_void, CString};
+
+    use flappybirb::WORLD_SIZE;
+    use glium::backend::Backend;
+    use glium::glutin::api::egl::context::PossiblyCurrentContext;
+    use glium::glutin::api::egl::device::Device;
+    use glium::glutin::api::egl::display::Display;
+    use glium::glutin::config::{ConfigSurfaceTypes, ConfigTemplateBuilder};
+    use glium::glutin::context::{ContextApi, ContextAttributesBuilder, Version};
+    use glium::glutin::prelude::*;
+
+    use super::{Context, Rc};
+
+    /// OpenGL context without any window, all drawing goes to framebuffer objects
+    struct Surfaceless {
+        display: Display,
+        context: PossiblyCurrentContext,
+    }
+
+    unsafe impl Backend for Surfaceless {
+        fn swap_buffers(&self) -> Result<(), glium::SwapBuffersError> {
+            Ok(())
+        }
+
+        unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+            let symbol = CString::new(symbol).unwrap();
+            self.display.get_proc_address(&symbol)
+        }
+
+        fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+            WORLD_SIZE
+        }
+
+        fn resize(&self, _: (u32, u32)) {}
+
+        fn is_current(&self) -> bool {
+            self.context.is_current()
+        }
+
+        unsafe fn make_current(&self) {
+            self.context.make_current_surfaceless().unwrap();
+        }
+    }
+
+    pub fn headless_context() -> Result<Rc<Context>, String> {
+        let device = Device::query_devices()
+            .map_err(|err| err.to_string())?
+            .next()
+            .ok_or("no EGL device")?;
+        let display =
+            unsafe { Display::with_device(&device, None) }.map_err(|err| err.to_string())?;
+        let template = ConfigTemplateBuilder::new()
+            .with_surface_type(ConfigSurfaceTypes::empty())
+            .build();
+        let config = unsafe { display.find_configs(template) }
+            .map_err(|err| err.to_string())?
+            .next()
+            .ok_or("no EGL config")?;
+        let attributes = ContextAttributesBuilder::new()
+            .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
+            .build(None);
+        let context = unsafe { display.create_context(&config, &attributes) }
+            .and_then(|context| context.make_current_surfaceless())
+            .map_err(|err| err.to_string())?;
+
+        unsafe { Context::new(Surfaceless { display, context }, false, Default::default()) }
+            .map_err(|err| err.to_string())
+    }
+}
+
+#[derive(Default)]
+struct Measurement {
+    draw_calls: usize,
+    max_draw_calls: usize,
+    time: Duration,
+}
+
+impl Measurement {
+    fn add(&mut self, draw_calls: usize, time: Duration) {
+        self.draw_calls += draw_calls;
+        self.max_draw_calls = self.max_draw_calls.max(draw_calls);
+        self.time += time;
+    }
+
+    fn report(&self, name: &str) {
+        println!(
+            "{name}: {:.2} draw calls per frame (at most {}), {:?} per frame",
+            self.draw_calls as f64 / FRAMES as f64,
+            self.max_draw_calls,
+            self.time / FRAMES,
+        );
+    }
+}
+
+fn main() {
+    let context = match headless_context() {
+        Ok(context) => context,
+        Err(err) => {
+            println!("Skipped, no OpenGL context: {err}");
+            return;
+        }
+    };
+
+    let mut atlas = AtlasBuilder::new();
+    let background = BackgroundSprite::new(&mut atlas);
+    let pipes = PipesSprite::new(&mut atlas);
+    let ground = GroundSprite::new(&mut atlas);
+    let bird = BirdSprite::new(&mut atlas);
+    let ui = Ui::new(&mut atlas);
+    let sprites = atlas.len();
+    let packed = atlas.pack();
+    let (width, height) = packed.image.dimensions();
+    println!("Atlas: {sprites} sprites packed into {width}x{height}");
+
+    let mut renderer = SpriteRenderer::new(&context, packed, WORLD_SIZE, false);
+    let texture = SrgbTexture2d::empty(&context, WORLD_SIZE.0, WORLD_SIZE.1).unwrap();
+    let mut target = SimpleFrameBuffer::new(&context, &texture).unwrap();
+
+    let mut world = GameWorld::new(WORLD_SIZE, 0, Config::default());
+    world.hit_detection = false;
+    world.start(0);
+
+    let mut batch = SpriteBatch::new();
+    let mut single = SpriteBatch::new();
+    let (mut batched, mut unbatched) = (Measurement::default(), Measurement::default());
+    for frame in 0..FRAMES {
+        if frame % FLAP_INTERVAL == 0 {
+            world.flap();
+        }
+        world.step(SIMULATION_STEP);
+
+        batch.clear();
+        background.render(&mut batch, &world);
+        pipes.render(&mut batch, &world);
+        ground.render(&mut batch, &world);
+        bird.render(&mut batch, &world);
+        ui.render(&mut batch, &world);
+
+        let (started, draw_calls) = (Instant::now(), renderer.draw_calls());
+        target.clear_color(0.0, 0.0, 0.0, 1.0);
+        renderer.draw(&mut target, &batch);
+        context.finish();
+        batched.add(renderer.draw_calls() - draw_calls, started.elapsed());
+
+        let (started, draw_calls) = (Instant::now(), renderer.draw_calls());
+        target.clear_color(0.0, 0.0, 0.0, 1.0);
+        for quad in batch.quads() {
+            single.clear();
+            single.push_quad(*quad);
+            renderer.draw(&mut target, &single);
+        }
+        context.finish();
+        unbatched.add(renderer.draw_calls() - draw_calls, started.elapsed());
+    }
+
+    println!("Frames: {FRAMES}");
+    unbatched.report("One draw per quad");
+    batched.report("Batched");
+}
@@ -1,16 +1,16 @@
 #version 330 core
 
 in vec2 sprite_uv;
+in float sprite_transparency;
 
 uniform sampler2D sprite;
-uniform vec2 pan;
-uniform vec2 flip;
 
 out vec4 FragColor;
 
 void main() {
-    FragColor = texture(sprite, (sprite_uv - flip) + pan);
+    FragColor = texture(sprite, sprite_uv);
     if (FragColor.a <= 0.5) {
         discard;
     }
+    FragColor.a *= 1.0 - sprite_transparency;
 }
@@ -2,14 +2,15 @@
 
 layout(location=0) in vec2 position;
 layout(location=1) in vec2 uv;
+layout(location=2) in float transparency;
 
-// TODO: Use this
-uniform mat4 model;
 uniform mat4 projection;
 
 out vec2 sprite_uv;
+out float sprite_transparency;
 
 void main() {
-    gl_Position = projection * model * vec4(position, 0.0, 1.0);
+    gl_Position = projection * vec4(position, 0.0, 1.0);
     sprite_uv = uv;
+    sprite_transparency = transparency;
 }
@@ -0,0 +1,151 @@
+//! Packing of all sprites into a single texture
+//!
+//! Sprites are added to an [`AtlasBuilder`] at startup, which hands out a [`Texture`] for
+//! each. [`AtlasBuilder::pack`] then lays them out on the CPU, so the layout can be
+//! built and inspected without a display, and [`PackedAtlas::upload`] creates the one
+//! GPU texture they are all drawn from.
+
+use glium::backend::Facade;
+use glium::texture::SrgbTexture2d;
+use image::RgbaImage;
+
+use crate::texture::Texture;
+
+/// Width of the atlas, sprites are placed on shelves from the top down
+const ATLAS_WIDTH: u32 = 1024;
+/// Pixels around every sprite, filled with its edge so filtering never picks up a neighbour
+const PADDING: u32 = 1;
+
+/// Where a sprite ended up in the atlas, in texture coordinates
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Region {
+    pub uv_min: [f32; 2],
+    pub uv_max: [f32; 2],
+}
+
+#[derive(Default)]
+pub struct AtlasBuilder {
+    images: Vec<RgbaImage>,
+}
+
+impl AtlasBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, image: RgbaImage) -> Texture {
+        let texture = Texture {
+            index: self.images.len(),
+            size: image.dimensions(),
+        };
+        self.images.push(image);
+        texture
+    }
+
+    /// Number of sprites added so far
+    pub fn len(&self) -> usize {
+        self.images.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty()
+    }
+
+    /// Lay out all sprites on shelves, tallest first
+    pub fn pack(self) -> PackedAtlas {
+        let mut order: Vec<usize> = (0..self.images.len()).collect();
+        order.sort_by_key(|&index| std::cmp::Reverse(self.images[index].height()));
+
+        let width = self
+            .images
+            .iter()
+            .map(|image| image.width() + 2 * PADDING)
+            .max()
+            .unwrap_or(0)
+            .max(ATLAS_WIDTH);
+
+        // Top left corner of every padded sprite
+        let mut positions = vec![(0, 0); self.images.len()];
+        let (mut x, mut y, mut shelf_height) = (0, 0, 0);
+        for &index in &order {
+            let (sprite_width, sprite_height) = self.images[index].dimensions();
+            let (padded_width, padded_height) =
+                (sprite_width + 2 * PADDING, sprite_height + 2 * PADDING);
+            if x + padded_width > width {
+                x = 0;
+                y += shelf_height;
+                shelf_height = 0;
+            }
+            positions[index] = (x, y);
+            x += padded_width;
+            shelf_height = shelf_height.max(padded_height);
+        }
+        let height = (y + shelf_height).max(1);
+
+        let mut atlas = RgbaImage::new(width, height);
+        let mut regions = Vec::with_capacity(self.images.len());
+        for (image, &(x, y)) in self.images.iter().zip(&positions) {
+            let (sprite_width, sprite_height) = image.dimensions();
+            for padded_y in 0..sprite_height + 2 * PADDING {
+                for padded_x in 0..sprite_width + 2 * PADDING {
+                    let source_x = padded_x.saturating_sub(PADDING).min(sprite_width - 1);
+                    let source_y = padded_y.saturating_sub(PADDING).min(sprite_height - 1);
+                    atlas.put_pixel(
+                        x + padded_x,
+                        y + padded_y,
+                        *image.get_pixel(source_x, source_y),
+                    );
+                }
+            }
+
+            let (left, top) = ((x + PADDING) as f32, (y + PADDING) as f32);
+            regions.push(Region {
+                uv_min: [left / width as f32, top / height as f32],
+                uv_max: [
+                    (left + sprite_width as f32) / width as f32,
+                    (top + sprite_height as f32) / height as f32,
+                ],
+            });
+        }
+
+        PackedAtlas {
+            image: atlas,
+            regions,
+        }
+    }
+}
+
+/// Atlas image with the region of every sprite, indexed like the [`Texture`]s handed out
+pub struct PackedAtlas {
+    pub image: RgbaImage,
+    pub regions: Vec<Region>,
+}
+
+impl PackedAtlas {
+    pub fn region(&self, texture: &Texture) -> Region {
+        self.regions[texture.index]
+    }
+
+    pub fn upload(self, facade: &impl Facade) -> Atlas {
+        let size = self.image.dimensions();
+        let image = glium::texture::RawImage2d::from_raw_rgba(self.image.into_raw(), size);
+        let texture = SrgbTexture2d::new(facade, image).unwrap();
+
+        Atlas {
+            texture,
+            regions: self.regions,
+        }
+    }
+}
+
+/// The atlas on the GPU
+pub struct Atlas {
+    pub texture: SrgbTexture2d,
+    regions: Vec<Region>,
+}
+
+impl Atlas {
+    pub fn region(&self, texture: &Texture) -> Region {
+        self.regions[texture.index]
+    }
+}
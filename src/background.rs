@@ -1,71 +1,51 @@
-use crate::gamestate::{GameState, PlayState, Update};
-use crate::renderer::{Render, RenderOptions, SpriteRenderer};
-use glium::glutin::surface::WindowSurface;
-use glium::{Display, Frame, Surface};
+use crate::atlas::AtlasBuilder;
+use crate::renderer::{Render, RenderOptions, SpriteBatch};
+use crate::world::background::TextureVariant;
+use crate::world::GameWorld;
 use nalgebra as na;
-use std::time::Duration;
 
 use crate::texture::Texture;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub enum TextureVariant {
-    Day,
-    Night,
-}
-
-pub struct Background {
+/// Draws the parallax background in the variant selected on the simulated background
+pub struct BackgroundSprite {
     night_texture: Texture,
     day_texture: Texture,
-    offset: f32,
-    speed: f32,
-    pub texture_variant: TextureVariant,
 }
 
-impl Background {
-    pub fn new(display: &Display<WindowSurface>) -> Self {
+impl BackgroundSprite {
+    pub fn new(atlas: &mut AtlasBuilder) -> Self {
         let night_texture = Texture::from_bytes(
             include_bytes!("../assets/sprites/background-night.png"),
-            display,
+            atlas,
         );
         let day_texture = Texture::from_bytes(
             include_bytes!("../assets/sprites/background-day.png"),
-            display,
+            atlas,
         );
 
         Self {
             night_texture,
             day_texture,
-            offset: 0.0,
-            speed: 0.085,
-            texture_variant: TextureVariant::Night,
         }
     }
 }
 
-impl Render for Background {
-    fn render(&self, frame: &mut Frame, renderer: &SpriteRenderer, _game_state: &GameState) {
-        let size = frame.get_dimensions();
-        let pan = na::Vector2::new(self.offset, 0.0);
-        renderer.render(
-            frame,
-            if self.texture_variant == TextureVariant::Night {
+impl Render for BackgroundSprite {
+    fn render(&self, batch: &mut SpriteBatch, world: &GameWorld) {
+        let size = world.state.viewport_size;
+        let background = &world.background;
+        let offset = background.interpolated_offset(world.state.interpolation);
+        batch.push(
+            if background.texture_variant == TextureVariant::Night {
                 &self.night_texture
             } else {
                 &self.day_texture
             },
             RenderOptions {
                 size: na::Vector2::new(size.0 as f32, size.1 as f32),
-                pan,
+                pan: offset,
                 ..RenderOptions::default()
             },
         );
     }
 }
-
-impl Update for Background {
-    fn update(&mut self, dt: Duration, game_state: &mut GameState) {
-        if matches!(game_state.state, PlayState::Playing) {
-            self.offset += dt.as_secs_f32() * self.speed;
-        }
-    }
-}
@@ -0,0 +1,98 @@
+//! Command line arguments of the `flappybirb` binary
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use clap::Parser;
+
+use crate::config::Preset;
+use crate::world::background::TextureVariant;
+use crate::world::bird::BirdColor;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Simple Flappy bird clone written using Glium")]
+pub struct Args {
+    /// Seed for the pipe sequence, every run uses a fresh random seed if not given
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Path of the TOML config file
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Difficulty preset: easy, normal, hard or custom to use the config file as it is
+    #[arg(long, value_name = "PRESET")]
+    pub preset: Option<Preset>,
+
+    /// Color of the bird: blue, red or yellow
+    #[arg(long, value_name = "COLOR")]
+    pub bird_color: Option<BirdColor>,
+
+    /// Background variant: day or night
+    #[arg(long, value_name = "VARIANT")]
+    pub background: Option<TextureVariant>,
+
+    /// Window size as WIDTHxHEIGHT, overrides the config file
+    #[arg(long, value_name = "WIDTHxHEIGHT")]
+    pub window_size: Option<WindowSize>,
+
+    /// Start in borderless fullscreen, overrides the config file
+    #[arg(long)]
+    pub fullscreen: bool,
+
+    /// Start with all sound muted
+    #[arg(long)]
+    pub mute: bool,
+
+    /// Start with the options window (F5) open
+    #[arg(long)]
+    pub debug: bool,
+
+    /// Disable collisions with pipes and ground
+    #[arg(long)]
+    pub no_hit_detection: bool,
+
+    /// Simulate a single run without a window and print the result
+    ///
+    /// Without --flap-every the bird flaps once and falls, which only checks that the game
+    /// starts up.
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Flap every TICKS simulation steps (120 per second) during a headless run
+    #[arg(long, value_name = "TICKS", requires = "headless", value_parser = clap::value_parser!(u64).range(1..))]
+    pub flap_every: Option<u64>,
+
+    /// Save every finished run to this replay file, overwriting the previous one
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+
+    /// Replay a recorded run without a window and check that it ends the same way
+    #[arg(long, value_name = "FILE", conflicts_with = "record")]
+    pub replay: Option<PathBuf>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WindowSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl FromStr for WindowSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (width, height) = s
+            .split_once('x')
+            .ok_or_else(|| format!("expected WIDTHxHEIGHT, got `{s}`"))?;
+        let parse = |value: &str| match value.parse::<u32>() {
+            Ok(0) | Err(_) => Err(format!("`{value}` is not a positive integer")),
+            Ok(value) => Ok(value),
+        };
+
+        Ok(Self {
+            width: parse(width)?,
+            height: parse(height)?,
+        })
+    }
+}
@@ -0,0 +1,544 @@
+//! Game tunables loaded from a TOML file
+//!
+//! Every field has a default, so a config file only needs the values it changes. A file
+//! without a `preset` is [`Preset::Custom`], so its values are used as they are:
+//! ```toml
+//! [bird]
+//! gravity = 700.0
+//!
+//! [window]
+//! width = 500
+//!
+//! [input]
+//! flap = [{ key = "Up" }, { mouse = "Left" }]
+//! ```
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use winit::event::{MouseButton, VirtualKeyCode};
+
+use crate::input::{Action, Binding, GamepadButton, MAX_HOLD_TO_FLAP_RATE};
+use crate::world::difficulty::Curve;
+use crate::world::pipes::MAX_APERTURE_PERCENT;
+
+const FILE_NAME: &str = "config.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Overrides the bird physics and pipe layout below unless it is [`Preset::Custom`]
+    #[serde(default = "file_preset")]
+    pub preset: Preset,
+    pub bird: BirdConfig,
+    pub pipes: PipesConfig,
+    pub scrolling: ScrollingConfig,
+    pub difficulty: DifficultyConfig,
+    pub window: WindowConfig,
+    pub input: InputConfig,
+}
+
+/// A config file that doesn't name a preset is tuned by hand
+fn file_preset() -> Preset {
+    Preset::Custom
+}
+
+/// Named difficulty bundling bird physics, scroll speed and pipe layout
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    Easy = 0,
+    #[default]
+    Normal = 1,
+    Hard = 2,
+    /// Whatever the config file says
+    Custom = 3,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [Preset::Easy, Preset::Normal, Preset::Hard, Preset::Custom];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Easy => "Easy",
+            Preset::Normal => "Normal",
+            Preset::Hard => "Hard",
+            Preset::Custom => "Custom",
+        }
+    }
+
+    /// `config` with the values of this preset, [`Preset::Custom`] leaves it as it is
+    pub fn apply(self, config: &Config) -> Config {
+        // Gravity, upwards force, scroll speed factor, aperture and gap between pairs
+        let (gravity, upwards_force, speed, aperture_percent, gap_percent) = match self {
+            Preset::Easy => (500.0, 280.0, 0.8, 0.20, 0.26),
+            Preset::Normal => (600.0, 300.0, 1.0, 0.15, 0.20),
+            Preset::Hard => (700.0, 320.0, 1.2, 0.13, 0.17),
+            Preset::Custom => {
+                return Config {
+                    preset: self,
+                    ..config.clone()
+                }
+            }
+        };
+
+        let mut config = config.clone();
+        config.preset = self;
+        config.bird.gravity = gravity;
+        config.bird.upwards_force = upwards_force;
+        config.pipes.speed = PipesConfig::default().speed * speed;
+        config.pipes.aperture_percent = aperture_percent;
+        config.pipes.gap_percent = gap_percent;
+        config.scrolling.background_speed = ScrollingConfig::default().background_speed * speed;
+        config.scrolling.ground_speed = ScrollingConfig::default().ground_speed * speed;
+        config
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "easy" => Ok(Preset::Easy),
+            "normal" => Ok(Preset::Normal),
+            "hard" => Ok(Preset::Hard),
+            "custom" => Ok(Preset::Custom),
+            _ => Err(format!(
+                "unknown preset `{s}`, expected easy, normal, hard or custom"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BirdConfig {
+    /// Downwards acceleration in pixels per second squared
+    pub gravity: f32,
+    /// Upwards velocity set by a flap in pixels per second
+    pub upwards_force: f32,
+    /// Seconds each frame of the flap animation is shown
+    pub flap_frame_duration: f32,
+    /// Nose-up angle in degrees the bird snaps to when flapping, negative is up
+    pub min_rotation: f32,
+    /// Nosedive angle in degrees the bird pitches down to while falling
+    pub max_rotation: f32,
+    /// Degrees per second the bird pitches down while falling
+    pub rotation_speed: f32,
+}
+
+impl Default for BirdConfig {
+    fn default() -> Self {
+        Self {
+            gravity: 600.0,
+            upwards_force: 300.0,
+            flap_frame_duration: 0.25,
+            min_rotation: -20.0,
+            max_rotation: 90.0,
+            rotation_speed: 360.0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PipesConfig {
+    /// Viewport widths scrolled per second
+    pub speed: f32,
+    /// Height of the opening between a pair as a fraction of the viewport height
+    pub aperture_percent: f32,
+    /// Horizontal space between pairs as a fraction of the viewport width
+    pub gap_percent: f32,
+    /// Width of a pipe as a fraction of the viewport width
+    pub width_percent: f32,
+}
+
+impl Default for PipesConfig {
+    fn default() -> Self {
+        Self {
+            speed: 0.15,
+            aperture_percent: 0.15,
+            gap_percent: 0.20,
+            width_percent: 0.10,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScrollingConfig {
+    /// Background texture widths scrolled per second
+    pub background_speed: f32,
+    /// Ground texture widths scrolled per second
+    pub ground_speed: f32,
+}
+
+impl Default for ScrollingConfig {
+    fn default() -> Self {
+        Self {
+            background_speed: 0.085,
+            ground_speed: 0.15,
+        }
+    }
+}
+
+/// How the game gets harder as the score goes up
+///
+/// Curves are lists of `{ score, value }` points sorted by score, values in between are
+/// interpolated linearly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DifficultyConfig {
+    /// Factor applied to all scroll speeds
+    pub speed: Curve,
+    /// Factor applied to `pipes.aperture_percent`
+    pub aperture: Curve,
+    /// Fraction `pipes.gap_percent` randomly varies by between pairs
+    pub spacing_variation: Curve,
+    pub max_speed_multiplier: f32,
+    pub min_aperture_percent: f32,
+    pub max_spacing_variation: f32,
+}
+
+impl Default for DifficultyConfig {
+    fn default() -> Self {
+        Self {
+            speed: Curve::new(&[(0, 1.0), (10, 1.15), (30, 1.35), (60, 1.5)]),
+            aperture: Curve::new(&[(0, 1.0), (10, 0.9), (40, 0.75)]),
+            spacing_variation: Curve::new(&[(0, 0.0), (5, 0.1), (30, 0.3)]),
+            max_speed_multiplier: 1.5,
+            min_aperture_percent: 0.11,
+            max_spacing_variation: 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowConfig {
+    /// Logical width the window opens with, the game is scaled to fit
+    pub width: u32,
+    /// Logical height the window opens with, the game is scaled to fit
+    pub height: u32,
+    /// Start in borderless fullscreen
+    pub fullscreen: bool,
+    /// Only scale the game by whole numbers when it fits, which keeps pixel art crisp
+    pub integer_scale: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: 700,
+            height: 970,
+            fullscreen: false,
+            integer_scale: true,
+        }
+    }
+}
+
+/// Inputs bound to each [`Action`], see [`crate::input::InputMap`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InputConfig {
+    pub flap: Vec<Binding>,
+    pub start: Vec<Binding>,
+    pub pause: Vec<Binding>,
+    pub toggle_debug: Vec<Binding>,
+    pub toggle_fullscreen: Vec<Binding>,
+    pub restart: Vec<Binding>,
+    pub screenshot: Vec<Binding>,
+    pub quit: Vec<Binding>,
+    /// Flaps per second while the flap input is held down, up to [`MAX_HOLD_TO_FLAP_RATE`],
+    /// 0 flaps once per press
+    pub hold_to_flap_rate: f32,
+}
+
+impl InputConfig {
+    pub fn bindings(&self, action: Action) -> &Vec<Binding> {
+        match action {
+            Action::Flap => &self.flap,
+            Action::Start => &self.start,
+            Action::Pause => &self.pause,
+            Action::ToggleDebug => &self.toggle_debug,
+            Action::ToggleFullscreen => &self.toggle_fullscreen,
+            Action::Restart => &self.restart,
+            Action::Screenshot => &self.screenshot,
+            Action::Quit => &self.quit,
+        }
+    }
+
+    pub fn bindings_mut(&mut self, action: Action) -> &mut Vec<Binding> {
+        match action {
+            Action::Flap => &mut self.flap,
+            Action::Start => &mut self.start,
+            Action::Pause => &mut self.pause,
+            Action::ToggleDebug => &mut self.toggle_debug,
+            Action::ToggleFullscreen => &mut self.toggle_fullscreen,
+            Action::Restart => &mut self.restart,
+            Action::Screenshot => &mut self.screenshot,
+            Action::Quit => &mut self.quit,
+        }
+    }
+}
+
+impl Default for InputConfig {
+    fn default() -> Self {
+        let confirm = vec![
+            Binding::Key(VirtualKeyCode::Space),
+            Binding::Mouse(MouseButton::Left),
+            Binding::Gamepad(GamepadButton::South),
+        ];
+
+        Self {
+            flap: confirm.clone(),
+            start: confirm,
+            pause: vec![
+                Binding::Key(VirtualKeyCode::Escape),
+                Binding::Key(VirtualKeyCode::P),
+                Binding::Gamepad(GamepadButton::Start),
+            ],
+            toggle_debug: vec![Binding::Key(VirtualKeyCode::F5)],
+            toggle_fullscreen: vec![
+                Binding::Key(VirtualKeyCode::F11),
+                Binding::AltKey(VirtualKeyCode::Return),
+            ],
+            restart: vec![
+                Binding::Key(VirtualKeyCode::R),
+                Binding::Gamepad(GamepadButton::Select),
+            ],
+            screenshot: vec![Binding::Key(VirtualKeyCode::F12)],
+            quit: Vec::new(),
+            hold_to_flap_rate: 0.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    /// A value is out of range, with the name of the offending key
+    Invalid(&'static str, String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) => write!(f, "could not read {}: {err}", path.display()),
+            ConfigError::Parse(path, err) => {
+                write!(f, "could not parse {}: {err}", path.display())
+            }
+            ConfigError::Invalid(key, reason) => write!(f, "invalid `{key}`: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// `<config dir>/flappybirb/config.toml`, if the platform has a config directory
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("flappybirb").join(FILE_NAME))
+    }
+
+    /// Load the config at `path`, or the one at [`Config::default_path`] if there is one
+    ///
+    /// Only an explicitly given `path` has to exist, otherwise defaults are used.
+    pub fn load_or_default(path: Option<&Path>) -> Result<Self, ConfigError> {
+        match path {
+            Some(path) => Self::load(path),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => Self::load(&path),
+                _ => Ok(Self::default()),
+            },
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| ConfigError::Io(path.to_path_buf(), err))?;
+        let config = Self::from_toml(&contents)
+            .map_err(|err| ConfigError::Parse(path.to_path_buf(), err))?;
+        config.validate()?;
+
+        log::info!("Loaded config from {}", path.display());
+        Ok(config)
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let contents = toml::to_string(self).map_err(std::io::Error::other)?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let BirdConfig {
+            gravity,
+            upwards_force,
+            flap_frame_duration,
+            min_rotation,
+            max_rotation,
+            rotation_speed,
+        } = self.bird;
+        positive("bird.gravity", gravity)?;
+        positive("bird.upwards_force", upwards_force)?;
+        positive("bird.flap_frame_duration", flap_frame_duration)?;
+        if !(min_rotation.is_finite() && max_rotation.is_finite() && min_rotation < max_rotation) {
+            return Err(ConfigError::Invalid(
+                "bird.min_rotation",
+                format!("{min_rotation} must be less than bird.max_rotation ({max_rotation})"),
+            ));
+        }
+        positive("bird.rotation_speed", rotation_speed)?;
+
+        let PipesConfig {
+            speed,
+            aperture_percent,
+            gap_percent,
+            width_percent,
+        } = self.pipes;
+        not_negative("pipes.speed", speed)?;
+        in_range(
+            "pipes.aperture_percent",
+            aperture_percent,
+            MAX_APERTURE_PERCENT,
+        )?;
+        positive("pipes.gap_percent", gap_percent)?;
+        in_range("pipes.width_percent", width_percent, 1.0)?;
+
+        not_negative(
+            "scrolling.background_speed",
+            self.scrolling.background_speed,
+        )?;
+        not_negative("scrolling.ground_speed", self.scrolling.ground_speed)?;
+        let rate = self.input.hold_to_flap_rate;
+        if !(0.0..=MAX_HOLD_TO_FLAP_RATE).contains(&rate) {
+            return Err(ConfigError::Invalid(
+                "input.hold_to_flap_rate",
+                format!("{rate} must not be negative and at most {MAX_HOLD_TO_FLAP_RATE}"),
+            ));
+        }
+
+        let difficulty = &self.difficulty;
+        curve("difficulty.speed", &difficulty.speed, positive)?;
+        curve("difficulty.aperture", &difficulty.aperture, positive)?;
+        let widest = difficulty
+            .aperture
+            .points()
+            .iter()
+            .map(|point| point.value)
+            .fold(0.0, f32::max);
+        if aperture_percent * widest > MAX_APERTURE_PERCENT {
+            return Err(ConfigError::Invalid(
+                "difficulty.aperture",
+                format!(
+                    "{widest} times pipes.aperture_percent ({aperture_percent}) must be at most \
+                     {MAX_APERTURE_PERCENT}"
+                ),
+            ));
+        }
+        curve(
+            "difficulty.spacing_variation",
+            &difficulty.spacing_variation,
+            not_negative,
+        )?;
+        positive(
+            "difficulty.max_speed_multiplier",
+            difficulty.max_speed_multiplier,
+        )?;
+        in_range(
+            "difficulty.min_aperture_percent",
+            difficulty.min_aperture_percent,
+            MAX_APERTURE_PERCENT,
+        )?;
+        // Pairs would touch or overlap at a variation of 1
+        let max_variation = difficulty.max_spacing_variation;
+        if !(0.0..1.0).contains(&max_variation) {
+            return Err(ConfigError::Invalid(
+                "difficulty.max_spacing_variation",
+                format!("{max_variation} must not be negative and less than 1"),
+            ));
+        }
+
+        if self.window.width == 0 {
+            return Err(ConfigError::Invalid("window.width", "must not be 0".into()));
+        }
+        if self.window.height == 0 {
+            return Err(ConfigError::Invalid(
+                "window.height",
+                "must not be 0".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+fn positive(key: &'static str, value: f32) -> Result<(), ConfigError> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid(
+            key,
+            format!("{value} must be greater than 0"),
+        ))
+    }
+}
+
+/// Check a curve has points sorted by score and values that pass `check`
+fn curve(
+    key: &'static str,
+    curve: &Curve,
+    check: fn(&'static str, f32) -> Result<(), ConfigError>,
+) -> Result<(), ConfigError> {
+    if curve.points().is_empty() {
+        return Err(ConfigError::Invalid(key, "needs at least one point".into()));
+    }
+    if curve
+        .points()
+        .windows(2)
+        .any(|pair| pair[0].score >= pair[1].score)
+    {
+        return Err(ConfigError::Invalid(
+            key,
+            "points must be sorted by increasing score".into(),
+        ));
+    }
+    curve
+        .points()
+        .iter()
+        .try_for_each(|point| check(key, point.value))
+}
+
+fn not_negative(key: &'static str, value: f32) -> Result<(), ConfigError> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid(
+            key,
+            format!("{value} must not be negative"),
+        ))
+    }
+}
+
+fn in_range(key: &'static str, value: f32, max: f32) -> Result<(), ConfigError> {
+    if value.is_finite() && value > 0.0 && value <= max {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid(
+            key,
+            format!("{value} must be greater than 0 and at most {max}"),
+        ))
+    }
+}
@@ -0,0 +1,210 @@
+//! Gamepad input through gilrs
+//!
+//! [`Gamepads`] turns controller events into [`GamepadButton`] presses for the
+//! [`crate::input::InputMap`]. Events can also be injected by hand, which is how it is
+//! driven when there is no gamepad subsystem and in tests. Without the `gamepad` feature
+//! gilrs isn't built at all and only injected events are seen.
+
+use std::collections::VecDeque;
+
+#[cfg(feature = "gamepad")]
+use gilrs::{Button, EventType, Gilrs};
+use winit::event::ElementState;
+
+use crate::input::GamepadButton;
+
+/// Library independent gamepad event, devices are identified by an opaque id
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadEvent {
+    Connected {
+        id: usize,
+        name: String,
+    },
+    Disconnected {
+        id: usize,
+    },
+    Button {
+        id: usize,
+        button: GamepadButton,
+        state: ElementState,
+    },
+}
+
+pub struct Gamepads {
+    /// `None` if the platform has no gamepad support or it failed to start
+    #[cfg(feature = "gamepad")]
+    gilrs: Option<Gilrs>,
+    injected: VecDeque<GamepadEvent>,
+    /// Connected devices by id and name, in connection order
+    connected: Vec<(usize, String)>,
+    /// Device that was last used
+    active: Option<usize>,
+}
+
+impl Gamepads {
+    /// Start listening for gamepads, falling back to [`Gamepads::without_backend`]
+    #[cfg(feature = "gamepad")]
+    pub fn new() -> Self {
+        match Gilrs::new() {
+            Ok(gilrs) => {
+                let connected = gilrs
+                    .gamepads()
+                    .map(|(id, gamepad)| (usize::from(id), gamepad.name().to_string()))
+                    .collect();
+                Self {
+                    gilrs: Some(gilrs),
+                    connected,
+                    ..Self::without_backend()
+                }
+            }
+            Err(err) => {
+                log::warn!("Gamepads are not available: {err}");
+                Self::without_backend()
+            }
+        }
+    }
+
+    /// Built without the `gamepad` feature, there is nothing to listen to
+    #[cfg(not(feature = "gamepad"))]
+    pub fn new() -> Self {
+        log::info!("Built without gamepad support");
+        Self::without_backend()
+    }
+
+    /// Only sees events passed to [`Gamepads::inject`]
+    pub fn without_backend() -> Self {
+        Self {
+            #[cfg(feature = "gamepad")]
+            gilrs: None,
+            injected: VecDeque::new(),
+            connected: Vec::new(),
+            active: None,
+        }
+    }
+
+    #[cfg(feature = "gamepad")]
+    pub fn is_available(&self) -> bool {
+        self.gilrs.is_some()
+    }
+
+    #[cfg(not(feature = "gamepad"))]
+    pub fn is_available(&self) -> bool {
+        false
+    }
+
+    /// Queue `event` to be handled by the next [`Gamepads::poll`]
+    pub fn inject(&mut self, event: GamepadEvent) {
+        self.injected.push_back(event);
+    }
+
+    /// Handle pending events and return the button changes in the order they happened
+    pub fn poll(&mut self) -> Vec<(GamepadButton, ElementState)> {
+        let mut events = self.backend_events();
+        events.extend(self.injected.drain(..));
+
+        let mut buttons = Vec::new();
+        for event in events {
+            match event {
+                GamepadEvent::Connected { id, name } => {
+                    log::info!("Gamepad connected: {name}");
+                    self.connected.retain(|(connected, _)| *connected != id);
+                    self.connected.push((id, name));
+                }
+                GamepadEvent::Disconnected { id } => {
+                    if let Some((_, name)) = self.connected.iter().find(|(known, _)| *known == id) {
+                        log::info!("Gamepad disconnected: {name}");
+                    }
+                    self.connected.retain(|(connected, _)| *connected != id);
+                    if self.active == Some(id) {
+                        self.active = None;
+                    }
+                }
+                GamepadEvent::Button { id, button, state } => {
+                    self.active = Some(id);
+                    buttons.push((button, state));
+                }
+            }
+        }
+        buttons
+    }
+
+    /// Events gilrs has seen since the last call
+    #[cfg(feature = "gamepad")]
+    fn backend_events(&mut self) -> Vec<GamepadEvent> {
+        let mut events = Vec::new();
+        if let Some(gilrs) = &mut self.gilrs {
+            while let Some(event) = gilrs.next_event() {
+                let id = usize::from(event.id);
+                let event = match event.event {
+                    EventType::Connected => GamepadEvent::Connected {
+                        id,
+                        name: gilrs.gamepad(event.id).name().to_string(),
+                    },
+                    EventType::Disconnected => GamepadEvent::Disconnected { id },
+                    EventType::ButtonPressed(button, _) => match map_button(button) {
+                        Some(button) => GamepadEvent::Button {
+                            id,
+                            button,
+                            state: ElementState::Pressed,
+                        },
+                        None => continue,
+                    },
+                    EventType::ButtonReleased(button, _) => match map_button(button) {
+                        Some(button) => GamepadEvent::Button {
+                            id,
+                            button,
+                            state: ElementState::Released,
+                        },
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    #[cfg(not(feature = "gamepad"))]
+    fn backend_events(&mut self) -> Vec<GamepadEvent> {
+        Vec::new()
+    }
+
+    /// Names of the connected gamepads
+    pub fn connected(&self) -> impl Iterator<Item = &str> {
+        self.connected.iter().map(|(_, name)| name.as_str())
+    }
+
+    /// Name of the gamepad that was used last, or else the first one connected
+    pub fn active(&self) -> Option<&str> {
+        self.active
+            .and_then(|active| self.connected.iter().find(|(id, _)| *id == active))
+            .or(self.connected.first())
+            .map(|(_, name)| name.as_str())
+    }
+}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn map_button(button: Button) -> Option<GamepadButton> {
+    match button {
+        Button::South => Some(GamepadButton::South),
+        Button::East => Some(GamepadButton::East),
+        Button::North => Some(GamepadButton::North),
+        Button::West => Some(GamepadButton::West),
+        Button::LeftTrigger => Some(GamepadButton::LeftTrigger),
+        Button::RightTrigger => Some(GamepadButton::RightTrigger),
+        Button::Select => Some(GamepadButton::Select),
+        Button::Start => Some(GamepadButton::Start),
+        Button::DPadUp => Some(GamepadButton::DPadUp),
+        Button::DPadDown => Some(GamepadButton::DPadDown),
+        Button::DPadLeft => Some(GamepadButton::DPadLeft),
+        Button::DPadRight => Some(GamepadButton::DPadRight),
+        _ => None,
+    }
+}
@@ -2,11 +2,13 @@ use std::time::Duration;
 
 use nalgebra as na;
 
+use crate::world::difficulty::Difficulty;
+
 pub trait Update {
     fn update(&mut self, dt: Duration, game_state: &mut GameState);
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Default)]
 pub struct BoundingBox {
     pub position: na::Vector2<f32>,
     pub size: na::Vector2<f32>,
@@ -25,8 +27,110 @@ impl BoundingBox {
     }
 }
 
+/// A rectangle rotated about its center
+#[derive(Debug, Copy, Clone, Default)]
+pub struct OrientedBoundingBox {
+    pub center: na::Vector2<f32>,
+    pub half_size: na::Vector2<f32>,
+    /// Clockwise rotation in degrees, matching `RenderOptions::rotation`
+    pub rotation: f32,
+}
+
+impl OrientedBoundingBox {
+    /// `bounding_box` rotated about its center
+    pub fn from_bounding_box(bounding_box: &BoundingBox, rotation: f32) -> Self {
+        Self {
+            center: bounding_box.position + bounding_box.size * 0.5,
+            half_size: bounding_box.size * 0.5,
+            rotation,
+        }
+    }
+
+    /// Unit vectors along the box's width and height
+    fn axes(&self) -> [na::Vector2<f32>; 2] {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        [na::Vector2::new(cos, sin), na::Vector2::new(-sin, cos)]
+    }
+
+    pub fn corners(&self) -> [na::Vector2<f32>; 4] {
+        let [x_axis, y_axis] = self.axes();
+        let x = x_axis * self.half_size.x;
+        let y = y_axis * self.half_size.y;
+        [
+            self.center - x - y,
+            self.center + x - y,
+            self.center + x + y,
+            self.center - x + y,
+        ]
+    }
+
+    /// Smallest axis-aligned box containing this one
+    pub fn bounds(&self) -> BoundingBox {
+        let corners = self.corners();
+        let min = corners
+            .iter()
+            .fold(corners[0], |min, corner| min.inf(corner));
+        let max = corners
+            .iter()
+            .fold(corners[0], |max, corner| max.sup(corner));
+        BoundingBox {
+            position: min,
+            size: max - min,
+        }
+    }
+
+    /// Separating axis test, touching boxes intersect like they do for [`BoundingBox`]
+    pub fn intersect(&self, other: &OrientedBoundingBox) -> bool {
+        let (corners, other_corners) = (self.corners(), other.corners());
+        let project = |corners: &[na::Vector2<f32>; 4], axis: &na::Vector2<f32>| {
+            corners
+                .iter()
+                .map(|corner| corner.dot(axis))
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), p| {
+                    (min.min(p), max.max(p))
+                })
+        };
+
+        self.axes().iter().chain(other.axes().iter()).all(|axis| {
+            let (min, max) = project(&corners, axis);
+            let (other_min, other_max) = project(&other_corners, axis);
+            !(max < other_min || other_max < min)
+        })
+    }
+
+    pub fn intersect_box(&self, other: &BoundingBox) -> bool {
+        self.intersect(&OrientedBoundingBox::from_bounding_box(other, 0.0))
+    }
+}
+
+/// Shape an object collides with
+#[derive(Debug, Copy, Clone)]
+pub enum Collider {
+    Box(BoundingBox),
+    Oriented(OrientedBoundingBox),
+}
+
+impl Collider {
+    pub fn intersect(&self, other: &Collider) -> bool {
+        match (self, other) {
+            (Collider::Box(a), Collider::Box(b)) => a.intersect(b),
+            (Collider::Oriented(a), Collider::Box(b))
+            | (Collider::Box(b), Collider::Oriented(a)) => a.intersect_box(b),
+            (Collider::Oriented(a), Collider::Oriented(b)) => a.intersect(b),
+        }
+    }
+}
+
 pub trait Hittable {
     fn bounding_boxes(&self, game_state: &GameState) -> Vec<BoundingBox>;
+
+    /// Shapes matching what is drawn, defaults to the bounding boxes
+    fn colliders(&self, game_state: &GameState) -> Vec<Collider> {
+        self.bounding_boxes(game_state)
+            .into_iter()
+            .map(Collider::Box)
+            .collect()
+    }
 }
 
 pub type Score = u32;
@@ -35,14 +139,36 @@ pub type Score = u32;
 pub enum PlayState {
     #[default]
     MainMenu,
+    /// Waiting for the first flap with the bird hovering at its start position
+    Ready,
     Playing,
+    /// The bird hit something and is falling to the ground
+    Dying,
     GameOver,
+    /// Nothing moves until resumed
+    Paused,
 }
 
 #[derive(Debug, Copy, Clone, Default)]
 pub struct GameState {
     pub state: PlayState,
+    /// Simulated time since `state` last changed
+    pub time_in_state: Duration,
+    /// Simulated time since the bird hit something, if it did during the current run
+    pub time_since_hit: Option<Duration>,
     pub score: Score,
+    pub best_score: Score,
+    /// Size of the world, which stays the same when the window is resized
     pub viewport_size: (u32, u32),
     pub fly_up: bool,
+    /// Seed for the pipe sequence of the current run
+    pub seed: u64,
+    /// Simulation steps since the current run started
+    pub tick: u64,
+    /// How far rendering is between the previous and the current simulation step, in `[0, 1)`
+    pub interpolation: f32,
+    /// Where the bird was after its last update, used by the pipes for scoring
+    pub bird_bounding_box: BoundingBox,
+    /// Speed and pipe layout for the current score
+    pub difficulty: Difficulty,
 }
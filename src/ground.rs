@@ -1,60 +1,34 @@
-use glium::glutin::surface::WindowSurface;
-use glium::{Display, Frame};
-use nalgebra_glm as glm;
-use std::time::Duration;
-
-use crate::gamestate::{BoundingBox, GameState, Hittable, PlayState, Update};
-use crate::renderer::{Render, RenderOptions, SpriteRenderer};
+use crate::atlas::AtlasBuilder;
+use crate::gamestate::{BoundingBox, Hittable};
+use crate::renderer::{Render, RenderOptions, SpriteBatch};
 use crate::texture::Texture;
+use crate::world::GameWorld;
 
-pub struct Ground {
+/// Draws the scrolling ground over the simulated ground's hit box
+pub struct GroundSprite {
     texture: Texture,
-    offset: f32,
-    speed: f32,
 }
 
-impl Ground {
-    pub fn new(display: &Display<WindowSurface>) -> Self {
-        let texture = Texture::from_bytes(include_bytes!("../assets/sprites/base.png"), display);
-        Self {
-            texture,
-            offset: 0.0,
-            speed: 0.15,
-        }
+impl GroundSprite {
+    pub fn new(atlas: &mut AtlasBuilder) -> Self {
+        let texture = Texture::from_bytes(include_bytes!("../assets/sprites/base.png"), atlas);
+        Self { texture }
     }
 }
 
-impl Render for Ground {
-    fn render(&self, frame: &mut Frame, renderer: &SpriteRenderer, game_state: &GameState) {
-        let pan = glm::vec2(self.offset, 0.0);
-        let BoundingBox { position, size } = self.bounding_boxes(game_state)[0];
-        renderer.render(
-            frame,
+impl Render for GroundSprite {
+    fn render(&self, batch: &mut SpriteBatch, world: &GameWorld) {
+        let game_state = &world.state;
+        let offset = world.ground.interpolated_offset(game_state.interpolation);
+        let BoundingBox { position, size } = world.ground.bounding_boxes(game_state)[0];
+        batch.push(
             &self.texture,
             RenderOptions {
                 position,
                 size,
-                pan,
+                pan: offset,
                 ..RenderOptions::default()
             },
         );
     }
 }
-
-impl Update for Ground {
-    fn update(&mut self, dt: Duration, game_state: &mut GameState) {
-        if matches!(game_state.state, PlayState::Playing) {
-            self.offset += dt.as_secs_f32() * self.speed;
-        }
-    }
-}
-
-impl Hittable for Ground {
-    fn bounding_boxes(&self, game_state: &GameState) -> Vec<BoundingBox> {
-        // Cover bottom 20% of window
-        let viewport_size = game_state.viewport_size;
-        let size = glm::vec2(viewport_size.0 as f32, viewport_size.1 as f32 * 0.20);
-        let position = glm::vec2(0.0, viewport_size.1 as f32 * 0.80);
-        vec![BoundingBox { position, size }]
-    }
-}
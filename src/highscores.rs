@@ -0,0 +1,190 @@
+//! Persistent table of the best runs
+
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Preset;
+use crate::gamestate::Score;
+use crate::world::background::TextureVariant;
+use crate::world::bird::BirdColor;
+
+/// Bump whenever the on-disk layout of [`HighScoreEntry`] changes
+const FORMAT_VERSION: u32 = 2;
+/// Tables from before presets existed, their runs all become [`Preset::Normal`] runs
+const PRESETLESS_VERSION: u32 = 1;
+const FILE_NAME: &str = "highscores.toml";
+
+/// Number of runs kept in the table of each preset
+pub const MAX_ENTRIES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub score: Score,
+    /// Seconds since the Unix epoch when the run ended
+    pub timestamp: u64,
+    pub bird_color: BirdColor,
+    pub background: TextureVariant,
+    #[serde(with = "crate::util::u64_as_string")]
+    pub seed: u64,
+    /// Runs from before presets existed were played on the normal settings
+    #[serde(default)]
+    pub preset: Preset,
+}
+
+impl HighScoreEntry {
+    /// Entry for a run that just ended
+    pub fn now(
+        score: Score,
+        bird_color: BirdColor,
+        background: TextureVariant,
+        seed: u64,
+        preset: Preset,
+    ) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+
+        Self {
+            score,
+            timestamp,
+            bird_color,
+            background,
+            seed,
+            preset,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct HighScoreFile {
+    version: u32,
+    entries: Vec<HighScoreEntry>,
+}
+
+#[derive(Debug, Default)]
+pub struct HighScores {
+    path: Option<PathBuf>,
+    entries: Vec<HighScoreEntry>,
+}
+
+impl HighScores {
+    /// `<data dir>/flappybirb/highscores.toml`, if the platform has a data directory
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("flappybirb").join(FILE_NAME))
+    }
+
+    /// Load the table from the default location, see [`HighScores::load`]
+    pub fn load_default() -> Self {
+        match Self::default_path() {
+            Some(path) => Self::load(path),
+            None => {
+                log::warn!("No user data directory, high scores will not be saved");
+                Self::default()
+            }
+        }
+    }
+
+    /// Load the table stored at `path`
+    ///
+    /// A missing, unreadable or corrupted file gives an empty table that will
+    /// overwrite it on the next save.
+    pub fn load(path: PathBuf) -> Self {
+        let entries = match Self::read_entries(&path) {
+            Ok(entries) => entries,
+            Err(err) => {
+                log::warn!("Starting with empty high scores: {err}");
+                Vec::new()
+            }
+        };
+
+        let mut high_scores = Self {
+            path: Some(path),
+            entries,
+        };
+        high_scores.sort_and_truncate();
+        high_scores
+    }
+
+    fn read_entries(path: &Path) -> Result<Vec<HighScoreEntry>, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| format!("could not read {}: {err}", path.display()))?;
+        let file: HighScoreFile = toml::from_str(&contents)
+            .map_err(|err| format!("could not parse {}: {err}", path.display()))?;
+        if file.version != FORMAT_VERSION && file.version != PRESETLESS_VERSION {
+            return Err(format!(
+                "{} has unsupported version {}",
+                path.display(),
+                file.version
+            ));
+        }
+
+        Ok(file.entries)
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let file = HighScoreFile {
+            version: FORMAT_VERSION,
+            entries: self.entries.clone(),
+        };
+        let contents = toml::to_string(&file).map_err(std::io::Error::other)?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)
+    }
+
+    /// Add a finished run, returning its rank among runs of the same preset if it made it
+    /// into the table
+    pub fn insert(&mut self, entry: HighScoreEntry) -> Option<usize> {
+        // Place after entries with the same score so older runs keep their rank
+        let rank = self
+            .entries(entry.preset)
+            .position(|existing| existing.score < entry.score)
+            .unwrap_or_else(|| self.entries(entry.preset).count());
+        if rank >= MAX_ENTRIES {
+            return None;
+        }
+
+        let index = self
+            .entries
+            .iter()
+            .position(|existing| existing.score < entry.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(index, entry);
+        self.truncate();
+        Some(rank)
+    }
+
+    /// Runs played with `preset`, best first
+    pub fn entries(&self, preset: Preset) -> impl Iterator<Item = &HighScoreEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.preset == preset)
+    }
+
+    pub fn best(&self, preset: Preset) -> Score {
+        self.entries(preset).next().map_or(0, |entry| entry.score)
+    }
+
+    fn sort_and_truncate(&mut self) {
+        self.entries.sort_by_key(|entry| Reverse(entry.score));
+        self.truncate();
+    }
+
+    /// Keep the best [`MAX_ENTRIES`] runs of every preset
+    fn truncate(&mut self) {
+        let mut counts = [0; Preset::ALL.len()];
+        self.entries.retain(|entry| {
+            counts[entry.preset as usize] += 1;
+            counts[entry.preset as usize] <= MAX_ENTRIES
+        });
+    }
+}
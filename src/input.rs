@@ -0,0 +1,288 @@
+//! Mapping of raw keys, mouse buttons and gamepad buttons to game actions
+//!
+//! The front end feeds window events to an [`InputMap`] as [`Binding`]s and then asks it
+//! which [`Action`]s were pressed, held or released this frame, so the game loop never
+//! looks at raw keycodes.
+
+use std::fmt;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use winit::event::{ElementState, MouseButton, VirtualKeyCode};
+
+use crate::config::InputConfig;
+
+/// Highest [`InputConfig::hold_to_flap_rate`], faster than anyone could tap
+pub const MAX_HOLD_TO_FLAP_RATE: f32 = 10.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Action {
+    Flap,
+    Start,
+    Pause,
+    ToggleDebug,
+    ToggleFullscreen,
+    Restart,
+    Screenshot,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::Flap,
+        Action::Start,
+        Action::Pause,
+        Action::ToggleDebug,
+        Action::ToggleFullscreen,
+        Action::Restart,
+        Action::Screenshot,
+        Action::Quit,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Flap => "Flap",
+            Action::Start => "Start",
+            Action::Pause => "Pause",
+            Action::ToggleDebug => "Toggle debug",
+            Action::ToggleFullscreen => "Toggle fullscreen",
+            Action::Restart => "Restart",
+            Action::Screenshot => "Screenshot",
+            Action::Quit => "Quit",
+        }
+    }
+}
+
+/// Controller buttons by position, independent of any gamepad library
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// A physical input that can be bound to an action
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Binding {
+    Key(VirtualKeyCode),
+    /// Key pressed while Alt is held down
+    AltKey(VirtualKeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl Binding {
+    /// Whether both are the same physical input, ignoring modifiers
+    fn same_input(&self, other: &Binding) -> bool {
+        match (self, other) {
+            (
+                Binding::Key(key) | Binding::AltKey(key),
+                Binding::Key(other) | Binding::AltKey(other),
+            ) => key == other,
+            _ => self == other,
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{key:?} key"),
+            Binding::AltKey(key) => write!(f, "Alt+{key:?} key"),
+            Binding::Mouse(button) => write!(f, "{button:?} mouse button"),
+            Binding::Gamepad(button) => write!(f, "Gamepad {button:?}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InputMap {
+    config: InputConfig,
+    /// Inputs that are currently down
+    held: Vec<Binding>,
+    /// Actions that went down since the last [`InputMap::end_frame`]
+    pressed: Vec<Action>,
+    /// Actions that went up since the last [`InputMap::end_frame`]
+    released: Vec<Action>,
+    /// Time the flap action has been held since it last flapped
+    flap_held_for: Duration,
+    /// Action the next pressed input gets bound to
+    capturing: Option<Action>,
+    /// Input that was just captured, its release shouldn't trigger anything
+    captured: Option<Binding>,
+}
+
+impl InputMap {
+    pub fn new(config: InputConfig) -> Self {
+        Self {
+            config,
+            held: Vec::new(),
+            pressed: Vec::new(),
+            released: Vec::new(),
+            flap_held_for: Duration::ZERO,
+            capturing: None,
+            captured: None,
+        }
+    }
+
+    pub fn config(&self) -> &InputConfig {
+        &self.config
+    }
+
+    pub fn config_mut(&mut self) -> &mut InputConfig {
+        &mut self.config
+    }
+
+    /// Record `binding` going to `state`
+    ///
+    /// Presses of an input that is already down, like OS key repeat, are ignored so a
+    /// physical press is only ever seen once. While capturing, the first pressed input is
+    /// bound to the captured action instead and triggers nothing.
+    pub fn handle(&mut self, binding: Binding, state: ElementState) {
+        if let Some(action) = self.capturing {
+            if state == ElementState::Pressed {
+                self.bind(action, binding);
+                self.capturing = None;
+                self.captured = Some(binding);
+            }
+            return;
+        }
+        if self.captured == Some(binding) {
+            if state == ElementState::Released {
+                self.captured = None;
+            }
+            return;
+        }
+
+        let was_held = Action::ALL.map(|action| self.held(action));
+        match state {
+            ElementState::Pressed if !self.held.contains(&binding) => self.held.push(binding),
+            // Alt may have changed while the key was down
+            ElementState::Released => self.held.retain(|held| !held.same_input(&binding)),
+            _ => return,
+        }
+
+        for (action, was_held) in Action::ALL.into_iter().zip(was_held) {
+            match (was_held, self.held(action)) {
+                (false, true) => {
+                    self.pressed.push(action);
+                    if action == Action::Flap {
+                        self.flap_held_for = Duration::ZERO;
+                    }
+                }
+                (true, false) => self.released.push(action),
+                _ => {}
+            }
+        }
+    }
+
+    /// Forget all inputs that are down without releasing their actions
+    ///
+    /// For when the window loses focus and won't see the inputs going up.
+    pub fn release_all(&mut self) {
+        self.held.clear();
+    }
+
+    /// Advance the hold to flap timer by `dt`, see [`InputConfig::hold_to_flap_rate`]
+    pub fn update(&mut self, dt: Duration) {
+        let rate = self.config.hold_to_flap_rate;
+        if rate <= 0.0 || !self.held(Action::Flap) || self.pressed(Action::Flap) {
+            return;
+        }
+
+        // Rates so low the period doesn't fit a `Duration` never repeat
+        let Ok(period) = Duration::try_from_secs_f32(1.0 / rate) else {
+            return;
+        };
+        self.flap_held_for += dt;
+        if self.flap_held_for >= period {
+            self.flap_held_for = (self.flap_held_for - period).min(period);
+            self.pressed.push(Action::Flap);
+        }
+    }
+
+    /// Start a new frame, forgetting what was pressed and released in the last one
+    pub fn end_frame(&mut self) {
+        self.pressed.clear();
+        self.released.clear();
+    }
+
+    /// Whether `action` went down this frame
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    /// Whether any input bound to `action` is down
+    pub fn held(&self, action: Action) -> bool {
+        self.config
+            .bindings(action)
+            .iter()
+            .any(|binding| self.held.contains(binding))
+    }
+
+    /// Whether `action` went up this frame
+    pub fn released(&self, action: Action) -> bool {
+        self.released.contains(&action)
+    }
+
+    /// Binding for `key` going down or up while Alt is or isn't held
+    ///
+    /// Alt only makes a difference to keys that have an [`Binding::AltKey`] binding, any
+    /// other key acts the same with or without it. While capturing, Alt is always kept so
+    /// Alt combinations can be bound.
+    pub fn key_binding(&self, key: VirtualKeyCode, alt: bool) -> Binding {
+        let alt_key = Binding::AltKey(key);
+        let alt_bound = Action::ALL
+            .into_iter()
+            .any(|action| self.bindings(action).contains(&alt_key));
+        if alt && (alt_bound || self.capturing.is_some()) {
+            alt_key
+        } else {
+            Binding::Key(key)
+        }
+    }
+
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.config.bindings(action)
+    }
+
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.config.bindings_mut(action);
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    pub fn unbind(&mut self, action: Action, binding: Binding) {
+        self.config
+            .bindings_mut(action)
+            .retain(|bound| *bound != binding);
+    }
+
+    /// Bind the next pressed input to `action`
+    pub fn capture(&mut self, action: Action) {
+        self.capturing = Some(action);
+    }
+
+    pub fn cancel_capture(&mut self) {
+        self.capturing = None;
+    }
+
+    pub fn capturing(&self) -> Option<Action> {
+        self.capturing
+    }
+
+    pub fn reset(&mut self) {
+        self.config = InputConfig::default();
+    }
+}
@@ -0,0 +1,31 @@
+//! Flappy Birb as a library
+//!
+//! The simulation in [`world`] has no windowing or GPU dependencies and can be driven
+//! directly by bots, tools and tests. The sprite modules draw a [`GameWorld`] with glium
+//! and are what the `flappybirb` binary uses.
+
+pub mod atlas;
+pub mod background;
+pub mod bird;
+pub mod cli;
+pub mod config;
+pub mod gamepad;
+pub mod gamestate;
+pub mod ground;
+pub mod highscores;
+pub mod input;
+pub mod pipes;
+pub mod renderer;
+pub mod replay;
+mod shader;
+pub mod texture;
+pub mod ui;
+mod util;
+mod vertex;
+pub mod world;
+
+pub use gamestate::{
+    BoundingBox, Collider, GameState, Hittable, OrientedBoundingBox, PlayState, Score, Update,
+};
+pub use renderer::Render;
+pub use world::{GameWorld, WorldEvent, SIMULATION_STEP, WORLD_SIZE};
@@ -1,35 +1,77 @@
-use std::time::Instant;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use glium::Surface;
+use clap::Parser;
+use glium::glutin::surface::WindowSurface;
+use glium::{Display, Surface};
 use soloud::{audio, FromExt, Soloud};
 use winit::dpi::LogicalSize;
-use winit::event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent};
+use winit::event::{
+    ElementState, Event, KeyboardInput, ModifiersState, MouseButton, Touch, TouchPhase, WindowEvent,
+};
 use winit::event_loop::ControlFlow;
-use winit::window::Icon;
-
-use crate::background::{Background, TextureVariant};
-use crate::bird::{Bird, BirdColor};
-use crate::gamestate::{GameState, Hittable, PlayState, Update};
-use crate::ground::Ground;
-use crate::pipes::Pipes;
-use crate::renderer::{Render, SpriteRenderer};
-use crate::ui::Ui;
-
-mod background;
-mod bird;
-mod gamestate;
-mod ground;
-mod pipes;
-mod renderer;
-mod shader;
-mod texture;
-mod ui;
-mod util;
-mod vertex;
+use winit::window::{Fullscreen, Icon};
+
+use flappybirb::atlas::AtlasBuilder;
+use flappybirb::background::BackgroundSprite;
+use flappybirb::bird::BirdSprite;
+use flappybirb::cli::Args;
+use flappybirb::config::{Config, InputConfig, Preset};
+use flappybirb::gamepad::Gamepads;
+use flappybirb::ground::GroundSprite;
+use flappybirb::highscores::{HighScoreEntry, HighScores};
+use flappybirb::input::{Action, Binding, InputMap, MAX_HOLD_TO_FLAP_RATE};
+use flappybirb::pipes::PipesSprite;
+use flappybirb::renderer::{Render, SpriteBatch, SpriteRenderer};
+use flappybirb::replay::Replay;
+use flappybirb::ui::Ui;
+use flappybirb::world::background::TextureVariant;
+use flappybirb::world::bird::BirdColor;
+use flappybirb::world::CollisionMode;
+use flappybirb::{GameWorld, PlayState, WorldEvent, SIMULATION_STEP, WORLD_SIZE};
+
+/// Upper bound on the time simulated per frame so a stall doesn't spiral
+const MAX_FRAME_TIME: Duration = Duration::from_millis(250);
+/// Headless runs without hit detection never end on their own, stop them after 10 minutes
+const HEADLESS_MAX_TICKS: u64 = 120 * 60 * 10;
+/// Time the game over screen ignores Start, so a flap let go as the bird lands doesn't skip it
+const GAME_OVER_INPUT_LOCKOUT: Duration = Duration::from_millis(500);
 
 fn main() {
     env_logger::init();
 
+    let args = Args::parse();
+    let mut config = match Config::load_or_default(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Error in config: {err}");
+            std::process::exit(1);
+        }
+    };
+    if let Some(size) = args.window_size {
+        config.window.width = size.width;
+        config.window.height = size.height;
+    }
+    if args.fullscreen {
+        config.window.fullscreen = true;
+    }
+    if let Some(preset) = args.preset {
+        config.preset = preset;
+    }
+    // Presets go on top of the file, so switching back to custom restores its values
+    let file_config = config;
+    let config = file_config.preset.apply(&file_config);
+
+    if let Some(path) = &args.replay {
+        run_replay(path);
+        return;
+    }
+
+    if args.headless {
+        run_headless(&args, config);
+        return;
+    }
+
     let event_loop = winit::event_loop::EventLoop::new();
 
     let image = image::load(
@@ -41,43 +83,133 @@ fn main() {
     let size = image.dimensions();
     let icon = Icon::from_rgba(image.into_raw(), size.0, size.1).ok();
     let window_builder = winit::window::WindowBuilder::new()
-        .with_inner_size(LogicalSize::new(700.0, 970.0))
+        .with_inner_size(LogicalSize::new(config.window.width, config.window.height))
         .with_title("Flappy Birb")
-        .with_resizable(false)
+        .with_resizable(true)
+        .with_fullscreen(
+            config
+                .window
+                .fullscreen
+                .then_some(Fullscreen::Borderless(None)),
+        )
         .with_window_icon(icon);
     let (window, display) = glium::backend::glutin::SimpleWindowBuilder::new()
         .set_window_builder(window_builder)
         .build(&event_loop);
 
     let mut egui_glium = egui_glium::EguiGlium::new(&display, &window, &event_loop);
-    let mut show_debug = false;
+    let mut show_debug = args.debug;
+    let mut take_screenshot = false;
+    let mut modifiers = ModifiersState::default();
+    let mut input = InputMap::new(config.input.clone());
+    let mut gamepads = Gamepads::new();
 
-    let sl = Soloud::default().unwrap();
+    let mut sl = Soloud::default().unwrap();
+    if args.mute {
+        sl.set_global_volume(0.0);
+    }
     let hit = audio::Wav::from_mem(include_bytes!("../assets/audio/hit.wav")).unwrap();
-    let _die = audio::Wav::from_mem(include_bytes!("../assets/audio/die.wav")).unwrap();
+    let die = audio::Wav::from_mem(include_bytes!("../assets/audio/die.wav")).unwrap();
     let _swoosh = audio::Wav::from_mem(include_bytes!("../assets/audio/swoosh.wav")).unwrap();
     let wing = audio::Wav::from_mem(include_bytes!("../assets/audio/wing.wav")).unwrap();
+    let point = audio::Wav::from_mem(include_bytes!("../assets/audio/point.wav")).unwrap();
 
-    let mut sprite_renderer = SpriteRenderer::new(&display);
+    let mut atlas = AtlasBuilder::new();
+    let background = BackgroundSprite::new(&mut atlas);
+    let pipes = PipesSprite::new(&mut atlas);
+    let ground = GroundSprite::new(&mut atlas);
+    let bird = BirdSprite::new(&mut atlas);
+    let ui = Ui::new(&mut atlas);
 
-    let mut game_state = GameState::default();
-    let mut hit_detection = true;
+    let mut sprite_renderer = SpriteRenderer::new(
+        &display,
+        atlas.pack(),
+        WORLD_SIZE,
+        config.window.integer_scale,
+    );
+    let mut batch = SpriteBatch::new();
 
-    let mut background = Background::new(&display);
-    let mut pipes = Pipes::new(&display);
-    let mut ground = Ground::new(&display);
-    let mut bird = Bird::new(&display);
-    let ui = Ui::new(&display);
+    let mut world = create_world(&args, config);
+    let mut high_scores = HighScores::load_default();
+    world.state.best_score = high_scores.best(world.config.preset);
 
     let mut previous_frame_time = Instant::now();
+    let mut accumulator = Duration::ZERO;
 
     event_loop.run(move |ev, _, control_flow| {
+        // Time spent paused is not simulated, so resuming doesn't make the bird jump
         let frame_time = Instant::now();
-        let dt = frame_time - previous_frame_time;
+        let elapsed = if matches!(world.state.state, PlayState::Paused) {
+            Duration::ZERO
+        } else {
+            (frame_time - previous_frame_time).min(MAX_FRAME_TIME)
+        };
+        accumulator += elapsed;
         previous_frame_time = frame_time;
 
+        // Step the simulation at a fixed rate regardless of how often events arrive
+        while accumulator >= SIMULATION_STEP {
+            accumulator -= SIMULATION_STEP;
+            world.step(SIMULATION_STEP);
+
+            for event in world.take_events() {
+                match event {
+                    WorldEvent::Flapped => {
+                        if sl.voice_count() == 0 {
+                            sl.play(&wing);
+                        }
+                    }
+                    WorldEvent::Scored => {
+                        sl.play(&point);
+                    }
+                    WorldEvent::Hit => {
+                        sl.play(&hit);
+                        sl.play(&die);
+                    }
+                    WorldEvent::GameOver => {
+                        record_high_score(&world, &mut high_scores);
+
+                        if let Some(path) = &args.record {
+                            if let Err(err) = Replay::capture(&world).save(path) {
+                                log::error!("Failed to save replay: {err}");
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        world.state.interpolation = accumulator.as_secs_f32() / SIMULATION_STEP.as_secs_f32();
+
+        // Gamepads don't go through winit, poll them alongside the window events
+        let mut inputs: Vec<_> = gamepads
+            .poll()
+            .into_iter()
+            .map(|(button, state)| (Binding::Gamepad(button), state))
+            .collect();
+
         let mut redraw = || {
             let repaint_after = egui_glium.run(&window, |ctx| {
+                if matches!(world.state.state, PlayState::MainMenu) {
+                    egui::Area::new("presets")
+                        .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -40.0])
+                        .show(ctx, |ui| {
+                            ui.horizontal(|ui| {
+                                for preset in Preset::ALL {
+                                    let selected = world.config.preset == preset;
+                                    let label = egui::RichText::new(preset.name()).size(24.0);
+                                    if ui.selectable_label(selected, label).clicked() {
+                                        // Preset runs are played with the standard collisions
+                                        if preset != Preset::Custom {
+                                            world.collision_mode = CollisionMode::default();
+                                        }
+                                        world.set_config(preset.apply(&file_config));
+                                        world.state.best_score = high_scores.best(preset);
+                                    }
+                                }
+                            });
+                        });
+                }
+
                 if !show_debug {
                     return;
                 }
@@ -87,53 +219,147 @@ fn main() {
                     .min_width(450.0)
                     .show(ctx, |ui| {
                         egui::ComboBox::from_label("Background style")
-                            .selected_text(if background.texture_variant == TextureVariant::Night {
-                                "Night"
-                            } else {
-                                "Day"
-                            })
+                            .selected_text(
+                                if world.background.texture_variant == TextureVariant::Night {
+                                    "Night"
+                                } else {
+                                    "Day"
+                                },
+                            )
                             .show_ui(ui, |ui| {
                                 if ui.button("Night").clicked() {
-                                    background.texture_variant = TextureVariant::Night;
+                                    world.background.texture_variant = TextureVariant::Night;
                                 }
                                 if ui.button("Day").clicked() {
-                                    background.texture_variant = TextureVariant::Day;
+                                    world.background.texture_variant = TextureVariant::Day;
                                 }
                             });
                         ui.separator();
-                        ui.checkbox(&mut hit_detection, "Hit Detection");
+                        // Replays can't reproduce changes made during the run they record
+                        let recording = args.record.is_some()
+                            && matches!(
+                                world.unpaused_state(),
+                                PlayState::Playing | PlayState::Dying
+                            );
+                        let tweaks = |world: &GameWorld| {
+                            (
+                                world.hit_detection,
+                                world.collision_mode,
+                                world.bird.gravity,
+                                world.bird.upwards_force,
+                                world.bird.color,
+                            )
+                        };
+                        let before = tweaks(&world);
+                        ui.add_enabled_ui(!recording, |ui| {
+                            ui.checkbox(&mut world.hit_detection, "Hit Detection");
+                            egui::ComboBox::from_label("Collision")
+                                .selected_text(match world.collision_mode {
+                                    CollisionMode::BoundingBox => "Bounding box",
+                                    CollisionMode::OrientedBox => "Oriented box",
+                                    CollisionMode::Pixel => "Pixel",
+                                })
+                                .show_ui(ui, |ui| {
+                                    if ui.button("Bounding box").clicked() {
+                                        world.collision_mode = CollisionMode::BoundingBox;
+                                    }
+                                    if ui.button("Oriented box").clicked() {
+                                        world.collision_mode = CollisionMode::OrientedBox;
+                                    }
+                                    if ui.button("Pixel").clicked() {
+                                        world.collision_mode = CollisionMode::Pixel;
+                                    }
+                                });
+
+                            ui.separator();
+                            ui.label("Gravity");
+                            ui.add(egui::DragValue::new(&mut world.bird.gravity).speed(0.1));
+
+                            ui.label("Upward Force");
+                            ui.add(egui::DragValue::new(&mut world.bird.upwards_force).speed(0.1));
+
+                            egui::ComboBox::from_label("Color")
+                                .selected_text(match world.bird.color {
+                                    BirdColor::Blue => "Blue",
+                                    BirdColor::Red => "Red",
+                                    BirdColor::Yellow => "Yellow",
+                                })
+                                .show_ui(ui, |ui| {
+                                    if ui.button("Blue").clicked() {
+                                        world.bird.color = BirdColor::Blue;
+                                    }
+                                    if ui.button("Red").clicked() {
+                                        world.bird.color = BirdColor::Red;
+                                    }
+                                    if ui.button("Yellow").clicked() {
+                                        world.bird.color = BirdColor::Yellow;
+                                    }
+                                });
+                        });
+                        // Tweaked runs don't belong on the leaderboard of a preset
+                        if tweaks(&world) != before && world.config.preset != Preset::Custom {
+                            world.config.preset = Preset::Custom;
+                            world.state.best_score = high_scores.best(Preset::Custom);
+                        }
 
                         ui.separator();
-                        ui.label("Gravity");
-                        ui.add(egui::DragValue::new(&mut bird.gravity).speed(0.1));
-
-                        ui.label("Upward Force");
-                        ui.add(egui::DragValue::new(&mut bird.upwards_force).speed(0.1));
-
-                        egui::ComboBox::from_label("Color")
-                            .selected_text(match bird.color {
-                                BirdColor::Blue => "Blue",
-                                BirdColor::Red => "Red",
-                                BirdColor::Yellow => "Yellow",
-                            })
-                            .show_ui(ui, |ui| {
-                                if ui.button("Blue").clicked() {
-                                    bird.color = BirdColor::Blue;
-                                }
-                                if ui.button("Red").clicked() {
-                                    bird.color = BirdColor::Red;
-                                }
-                                if ui.button("Yellow").clicked() {
-                                    bird.color = BirdColor::Yellow;
-                                }
-                            });
+                        ui.label(if !gamepads.is_available() {
+                            "Gamepads are not available".to_string()
+                        } else if let Some(name) = gamepads.active() {
+                            format!("Gamepad: {name}")
+                        } else {
+                            "No gamepad connected".to_string()
+                        });
 
                         ui.separator();
                         if ui.button("Reset game").clicked() {
-                            game_state.state = PlayState::MainMenu;
-                            bird.reset(&game_state);
-                            pipes.reset(&game_state);
+                            world.reset();
                         }
+
+                        ui.separator();
+                        ui.collapsing("Controls", |ui| {
+                            egui::Grid::new("bindings").striped(true).show(ui, |ui| {
+                                for action in Action::ALL {
+                                    ui.label(action.name());
+                                    ui.horizontal_wrapped(|ui| {
+                                        for binding in input.bindings(action).to_vec() {
+                                            if ui
+                                                .button(binding.to_string())
+                                                .on_hover_text("Click to unbind")
+                                                .clicked()
+                                            {
+                                                input.unbind(action, binding);
+                                            }
+                                        }
+                                        if input.capturing() == Some(action) {
+                                            if ui.button("Press any input...").clicked() {
+                                                input.cancel_capture();
+                                            }
+                                        } else if ui.button("+").clicked() {
+                                            input.capture(action);
+                                        }
+                                    });
+                                    ui.end_row();
+                                }
+                            });
+
+                            ui.add(
+                                egui::Slider::new(
+                                    &mut input.config_mut().hold_to_flap_rate,
+                                    0.0..=MAX_HOLD_TO_FLAP_RATE,
+                                )
+                                .text("Hold to flap (per second)"),
+                            );
+
+                            ui.horizontal(|ui| {
+                                if ui.button("Reset to defaults").clicked() {
+                                    input.reset();
+                                }
+                                if ui.button("Save").clicked() {
+                                    save_bindings(args.config.as_deref(), input.config());
+                                }
+                            });
+                        });
                     });
             });
 
@@ -147,89 +373,263 @@ fn main() {
 
                 frame.clear_color(0.0, 0.0, 0.0, 1.0);
 
-                background.render(&mut frame, &sprite_renderer, &game_state);
-                pipes.render(&mut frame, &sprite_renderer, &game_state);
-                ground.render(&mut frame, &sprite_renderer, &game_state);
-                bird.render(&mut frame, &sprite_renderer, &game_state);
-                ui.render(&mut frame, &sprite_renderer, &game_state);
+                batch.clear();
+                background.render(&mut batch, &world);
+                pipes.render(&mut batch, &world);
+                ground.render(&mut batch, &world);
+                bird.render(&mut batch, &world);
+                ui.render(&mut batch, &world);
+                sprite_renderer.draw(&mut frame, &batch);
 
                 egui_glium.paint(&display, &mut frame);
 
                 frame.finish().unwrap();
             }
+
+            if take_screenshot {
+                take_screenshot = false;
+                save_screenshot(&display);
+            }
         };
 
         match ev {
             Event::WindowEvent { event, .. } => {
-                match event {
-                    WindowEvent::CloseRequested | WindowEvent::Destroyed => control_flow.set_exit(),
+                // Clicks meant for the debug window shouldn't reach the game
+                let event_response = egui_glium.on_event(&event);
+                if event_response.repaint {
+                    window.request_redraw();
+                }
+                let changed = match event {
+                    WindowEvent::CloseRequested | WindowEvent::Destroyed => {
+                        control_flow.set_exit();
+                        None
+                    }
                     WindowEvent::KeyboardInput {
                         input:
                             KeyboardInput {
-                                virtual_keycode,
+                                virtual_keycode: Some(key),
                                 state,
                                 ..
                             },
                         ..
-                    } => match game_state.state {
-                        PlayState::Playing if virtual_keycode == Some(VirtualKeyCode::Space) => {
-                            game_state.fly_up = true;
-                            if sl.voice_count() == 0 {
-                                sl.play(&wing);
-                            }
-                        }
-                        _ if virtual_keycode == Some(VirtualKeyCode::Space)
-                            && state == ElementState::Released =>
-                        {
-                            bird.reset(&game_state);
-                            pipes.reset(&game_state);
-                            game_state.state = PlayState::Playing;
+                    } => Some((input.key_binding(key, modifiers.alt()), state)),
+                    WindowEvent::MouseInput { button, state, .. } => {
+                        Some((Binding::Mouse(button), state))
+                    }
+                    // Taps act like left clicks
+                    WindowEvent::Touch(Touch { phase, .. }) => match phase {
+                        TouchPhase::Started => {
+                            Some((Binding::Mouse(MouseButton::Left), ElementState::Pressed))
                         }
-                        _ if virtual_keycode == Some(VirtualKeyCode::F5)
-                            && state == ElementState::Released =>
-                        {
-                            show_debug = !show_debug;
+                        TouchPhase::Ended => {
+                            Some((Binding::Mouse(MouseButton::Left), ElementState::Released))
                         }
-                        _ => {}
+                        _ => None,
                     },
+                    WindowEvent::Focused(false) => {
+                        world.pause();
+                        input.release_all();
+                        None
+                    }
+                    WindowEvent::ModifiersChanged(state) => {
+                        modifiers = state;
+                        None
+                    }
                     WindowEvent::Resized(size) => {
-                        game_state.viewport_size = (size.width, size.height);
+                        display.resize((size.width, size.height));
                         sprite_renderer.viewport_resized((size.width, size.height));
+                        None
                     }
-                    _ => {}
+                    // Not every platform follows this up with a resize
+                    WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                        display.resize((new_inner_size.width, new_inner_size.height));
+                        sprite_renderer
+                            .viewport_resized((new_inner_size.width, new_inner_size.height));
+                        None
+                    }
+                    _ => None,
                 };
 
-                let event_response = egui_glium.on_event(&event);
-                if event_response.repaint {
-                    window.request_redraw();
-                }
+                // Releases always go through, so nothing pressed on the game stays held down
+                inputs.extend(changed.filter(|(_, state)| {
+                    *state == ElementState::Released || !event_response.consumed
+                }));
             }
             Event::RedrawEventsCleared => redraw(),
             Event::RedrawRequested(_) => redraw(),
             _ => {}
         }
 
-        background.update(dt, &mut game_state);
-        pipes.update(dt, &mut game_state);
-        ground.update(dt, &mut game_state);
-        bird.update(dt, &mut game_state);
+        for (binding, state) in inputs {
+            input.handle(binding, state);
+        }
+        input.update(elapsed);
+
+        if input.pressed(Action::Flap)
+            && matches!(world.state.state, PlayState::Ready | PlayState::Playing)
+        {
+            world.flap();
+        }
+        let start_allowed = match world.state.state {
+            PlayState::MainMenu => true,
+            PlayState::GameOver => world.state.time_in_state >= GAME_OVER_INPUT_LOCKOUT,
+            _ => false,
+        };
+        if input.released(Action::Start) && start_allowed {
+            world.start(args.seed.unwrap_or_else(rand::random));
+        }
+        // Dying plays out to the end so the result can't be skipped
+        let restart_allowed = !matches!(
+            world.unpaused_state(),
+            PlayState::MainMenu | PlayState::Dying
+        );
+        if input.released(Action::Restart) && restart_allowed {
+            world.start(args.seed.unwrap_or_else(rand::random));
+        }
+        if input.released(Action::Pause) {
+            world.toggle_pause();
+        }
+        if input.released(Action::ToggleFullscreen) {
+            let fullscreen = window.fullscreen().is_none();
+            window.set_fullscreen(fullscreen.then_some(Fullscreen::Borderless(None)));
+        }
+        if input.released(Action::ToggleDebug) {
+            show_debug = !show_debug;
+        }
+        if input.released(Action::Screenshot) {
+            take_screenshot = true;
+            window.request_redraw();
+        }
+        if input.released(Action::Quit) {
+            control_flow.set_exit();
+        }
+        input.end_frame();
+    });
+}
+
+/// Add the run in `world` to `high_scores`, saving them if it made it into the table
+fn record_high_score(world: &GameWorld, high_scores: &mut HighScores) {
+    let entry = HighScoreEntry::now(
+        world.state.score,
+        world.bird.color,
+        world.background.texture_variant,
+        world.state.seed,
+        world.config.preset,
+    );
+    if high_scores.insert(entry).is_some() {
+        if let Err(err) = high_scores.save() {
+            log::error!("Failed to save high scores: {err}");
+        }
+    }
+}
 
-        if hit_detection {
-            let bird_bb = bird.bounding_boxes(&game_state)[0];
-            let ground_bb = ground.bounding_boxes(&game_state)[0];
-            let pipe_intersect = pipes
-                .bounding_boxes(&game_state)
-                .iter()
-                .any(|bb| bb.intersect(&bird_bb));
+/// Store `bindings` in the config file at `path`, or the default one, keeping its other values
+fn save_bindings(path: Option<&Path>, bindings: &InputConfig) {
+    let Some(path) = path.map(Path::to_path_buf).or_else(Config::default_path) else {
+        log::warn!("No user config directory, bindings will not be saved");
+        return;
+    };
 
-            if matches!(game_state.state, PlayState::Playing)
-                && (bird_bb.intersect(&ground_bb) || pipe_intersect)
-            {
-                sl.play(&hit);
-                game_state.state = PlayState::GameOver;
+    let mut config = if path.exists() {
+        match Config::load(&path) {
+            Ok(config) => config,
+            Err(err) => {
+                log::error!("Not saving bindings over a broken config: {err}");
+                return;
             }
         }
+    } else {
+        Config::default()
+    };
+    config.input = bindings.clone();
 
-        game_state.fly_up = false;
-    });
+    match config.save(&path) {
+        Ok(()) => log::info!("Saved bindings to {}", path.display()),
+        Err(err) => log::error!("Failed to save bindings: {err}"),
+    }
+}
+
+/// Save what is currently on screen as a PNG in the user's pictures directory
+fn save_screenshot(display: &Display<WindowSurface>) {
+    let image: glium::texture::RawImage2d<u8> = match display.read_front_buffer() {
+        Ok(image) => image,
+        Err(err) => {
+            log::error!("Failed to read the screen: {err:?}");
+            return;
+        }
+    };
+    let Some(image) =
+        image::RgbaImage::from_raw(image.width, image.height, image.data.into_owned())
+    else {
+        return;
+    };
+    // OpenGL rows start at the bottom
+    let image = image::imageops::flip_vertical(&image);
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let path = dirs::picture_dir()
+        .unwrap_or_default()
+        .join(format!("flappybirb-{timestamp}.png"));
+    match image.save(&path) {
+        Ok(()) => log::info!("Saved screenshot to {}", path.display()),
+        Err(err) => log::error!("Failed to save screenshot: {err}"),
+    }
+}
+
+fn create_world(args: &Args, config: Config) -> GameWorld {
+    let mut world = GameWorld::new(WORLD_SIZE, args.seed.unwrap_or_else(rand::random), config);
+    world.hit_detection = !args.no_hit_detection;
+    if let Some(color) = args.bird_color {
+        world.bird.color = color;
+    }
+    if let Some(variant) = args.background {
+        world.background.texture_variant = variant;
+    }
+    world
+}
+
+/// Simulate a single run without a window until the bird dies, see `--flap-every`
+fn run_headless(args: &Args, config: Config) {
+    let mut world = create_world(args, config);
+    world.start(world.state.seed);
+    world.flap();
+
+    let mut ticks = 0;
+    while !matches!(world.state.state, PlayState::GameOver) && ticks < HEADLESS_MAX_TICKS {
+        if args
+            .flap_every
+            .is_some_and(|every| ticks > 0 && ticks % every == 0)
+        {
+            world.flap();
+        }
+        world.step(SIMULATION_STEP);
+        ticks += 1;
+    }
+
+    println!("seed: {}", world.state.seed);
+    println!("ticks: {ticks}");
+    println!("score: {}", world.state.score);
+}
+
+/// Replay the run recorded in `path` and exit with an error if it desyncs
+fn run_replay(path: &Path) {
+    let replay = match Replay::load(path) {
+        Ok(replay) => replay,
+        Err(err) => {
+            eprintln!("Could not load {}: {err}", path.display());
+            std::process::exit(1);
+        }
+    };
+
+    let outcome = replay.run();
+    println!("seed: {}", replay.seed);
+    println!("ticks: {} (recorded {})", outcome.ticks, replay.ticks);
+    println!("score: {} (recorded {})", outcome.score, replay.final_score);
+
+    if !replay.matches(&outcome) {
+        eprintln!("Replay desynced from the recording");
+        std::process::exit(1);
+    }
 }
@@ -1,53 +1,36 @@
-use std::time::Duration;
-
-use glium::glutin::surface::WindowSurface;
-use glium::{Display, Frame, Surface};
-use nalgebra_glm as glm;
-
-use crate::gamestate::{BoundingBox, GameState, Hittable, PlayState, Update};
-use crate::renderer::{Render, RenderOptions, SpriteRenderer};
+use crate::atlas::AtlasBuilder;
+use crate::gamestate::{BoundingBox, PlayState};
+use crate::renderer::{Render, RenderOptions, SpriteBatch};
 use crate::texture::Texture;
+use crate::world::GameWorld;
 
-const PIPE_APERTURE_PERCENT: f32 = 0.15;
-const PIPE_GAP_PERCENT: f32 = 0.20;
-
-pub struct Pipes {
+/// Draws every simulated pipe pair, flipping the top pipe of each
+pub struct PipesSprite {
     texture: Texture,
-    left_pipe_offset: f32,
-    speed: f32,
 }
 
-impl Pipes {
-    pub fn new(display: &Display<WindowSurface>) -> Self {
+impl PipesSprite {
+    pub fn new(atlas: &mut AtlasBuilder) -> Self {
         let texture =
-            Texture::from_bytes(include_bytes!("../assets/sprites/pipe-green.png"), display);
-        let width = display.get_framebuffer_dimensions().0 as f32;
-        let left_pipe_offset = width * 0.50;
-
-        Self {
-            texture,
-            left_pipe_offset,
-            speed: 0.15,
-        }
-    }
+            Texture::from_bytes(include_bytes!("../assets/sprites/pipe-green.png"), atlas);
 
-    pub fn reset(&mut self, game_state: &GameState) {
-        self.left_pipe_offset = game_state.viewport_size.0 as f32 * 0.50;
+        Self { texture }
     }
 }
 
-impl Render for Pipes {
-    fn render(&self, frame: &mut Frame, renderer: &SpriteRenderer, game_state: &GameState) {
-        if matches!(game_state.state, PlayState::Playing) {
-            let (width, height) = frame.get_dimensions();
-            let (width, height) = (width as f32, height as f32);
-            let mut pipe_offset = self.left_pipe_offset;
-            while pipe_offset < width {
-                // Top pipe
-                let position = glm::vec2(pipe_offset, 0.0);
-                let size = glm::vec2(width * 0.10, height * (0.50 - PIPE_APERTURE_PERCENT / 2.0));
-                renderer.render(
-                    frame,
+impl Render for PipesSprite {
+    fn render(&self, batch: &mut SpriteBatch, world: &GameWorld) {
+        let game_state = &world.state;
+        if matches!(
+            world.unpaused_state(),
+            PlayState::Playing | PlayState::Dying | PlayState::GameOver
+        ) {
+            for pair in world.pipes.pairs() {
+                let offset = pair.interpolated_offset(game_state.interpolation);
+                let [top, bottom] = pair.bounding_boxes_at(offset, game_state.viewport_size);
+
+                let BoundingBox { position, size } = top;
+                batch.push(
                     &self.texture,
                     RenderOptions {
                         position,
@@ -57,12 +40,8 @@ impl Render for Pipes {
                     },
                 );
 
-                // Bottom pipe
-                let position =
-                    glm::vec2(pipe_offset, height * (0.50 + PIPE_APERTURE_PERCENT / 2.0));
-                let size = glm::vec2(width * 0.10, height * (0.50 - PIPE_APERTURE_PERCENT / 2.0));
-                renderer.render(
-                    frame,
+                let BoundingBox { position, size } = bottom;
+                batch.push(
                     &self.texture,
                     RenderOptions {
                         position,
@@ -70,48 +49,7 @@ impl Render for Pipes {
                         ..RenderOptions::default()
                     },
                 );
-
-                pipe_offset += width * 0.10 + width * PIPE_GAP_PERCENT;
-            }
-        }
-    }
-}
-
-impl Update for Pipes {
-    fn update(&mut self, dt: Duration, game_state: &mut GameState) {
-        if matches!(game_state.state, PlayState::Playing) {
-            let width = game_state.viewport_size.0 as f32;
-            self.left_pipe_offset -= (dt.as_secs_f32() * self.speed) * width;
-            if self.left_pipe_offset < -(width * 0.10) {
-                self.left_pipe_offset += width * 0.10 + width * PIPE_GAP_PERCENT;
             }
         }
     }
 }
-
-impl Hittable for Pipes {
-    fn bounding_boxes(&self, game_state: &GameState) -> Vec<BoundingBox> {
-        let (width, height) = game_state.viewport_size;
-        let (width, height) = (width as f32, height as f32);
-
-        let mut bounding_boxes = Vec::new();
-
-        let mut pipe_offset = self.left_pipe_offset;
-
-        while pipe_offset < width {
-            // Top pipe
-            let position = glm::vec2(pipe_offset, 0.0);
-            let size = glm::vec2(width * 0.10, height * (0.50 - PIPE_APERTURE_PERCENT / 2.0));
-            bounding_boxes.push(BoundingBox { position, size });
-
-            // Bottom pipe
-            let position = glm::vec2(pipe_offset, height * (0.50 + PIPE_APERTURE_PERCENT / 2.0));
-            let size = glm::vec2(width * 0.10, height * (0.50 - PIPE_APERTURE_PERCENT / 2.0));
-            bounding_boxes.push(BoundingBox { position, size });
-
-            pipe_offset += width * 0.10 + width * PIPE_GAP_PERCENT;
-        }
-
-        bounding_boxes
-    }
-}
@@ -1,16 +1,18 @@
-use crate::gamestate::GameState;
+use crate::atlas::{Atlas, PackedAtlas, Region};
 use crate::shader::load_shader;
 use crate::texture::Texture;
+use crate::util::lerp;
 use crate::vertex::Vertex;
-use glium::glutin::surface::WindowSurface;
-use glium::{uniform, Display, Frame, Program, Surface, VertexBuffer};
+use crate::world::GameWorld;
+use glium::backend::{Context, Facade};
+use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter, SamplerWrapFunction};
+use glium::{uniform, Program, Rect, Surface, VertexBuffer};
 use nalgebra as na;
-use nalgebra::RealField;
 use nalgebra_glm as glm;
-use nalgebra_glm::TVec2;
+use std::rc::Rc;
 
 pub trait Render {
-    fn render(&self, frame: &mut Frame, renderer: &SpriteRenderer, game_state: &GameState);
+    fn render(&self, batch: &mut SpriteBatch, world: &GameWorld);
 }
 
 #[derive(Debug, Copy, Clone, Default)]
@@ -18,88 +20,311 @@ pub struct RenderOptions {
     pub position: na::Vector2<f32>,
     pub size: na::Vector2<f32>,
     pub rotation: f32,
-    pub pan: na::Vector2<f32>,
+    /// Horizontal scroll in texture widths, the texture wraps around
+    pub pan: f32,
     pub flip_vertical: bool,
     pub flip_horizontal: bool,
+    /// 0.0 draws the sprite as is, 1.0 makes it invisible
+    pub transparency: f32,
 }
 
-pub struct SpriteRenderer {
-    view: na::Matrix4<f32>,
-    shader_program: Program,
-    vertex_buffer: VertexBuffer<Vertex>,
+/// A sprite or part of one, placed in the world
+#[derive(Debug, Copy, Clone)]
+pub struct Quad {
+    pub texture: Texture,
+    pub position: na::Vector2<f32>,
+    pub size: na::Vector2<f32>,
+    /// Degrees clockwise around the center
+    pub rotation: f32,
+    /// Part of the texture drawn, `[0, 0]` to `[1, 1]` is all of it
+    pub uv_min: [f32; 2],
+    pub uv_max: [f32; 2],
+    pub flip_vertical: bool,
+    pub flip_horizontal: bool,
+    pub transparency: f32,
 }
 
-impl SpriteRenderer {
-    pub fn new(display: &Display<WindowSurface>) -> Self {
-        let shader_program = load_shader(display);
-        let (width, height) = display.get_framebuffer_dimensions();
-        let view = glm::ortho(0.0, width as f32, height as f32, 0.0, -1.0, 1.0);
+impl Quad {
+    /// Two triangles covering the quad, with texture coordinates inside `region`
+    fn vertices(&self, region: Region) -> [Vertex; 6] {
+        let [mut u_min, mut v_min] = self.uv_min;
+        let [mut u_max, mut v_max] = self.uv_max;
+        if self.flip_horizontal {
+            std::mem::swap(&mut u_min, &mut u_max);
+        }
+        if self.flip_vertical {
+            std::mem::swap(&mut v_min, &mut v_max);
+        }
 
-        let shape = Vertex::sprite_rectangle();
-        let vertex_buffer = glium::VertexBuffer::new(display, &shape).unwrap();
+        let center = self.position + self.size * 0.5;
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let vertex = |x: f32, y: f32| {
+            let offset = glm::vec2((x - 0.5) * self.size.x, (y - 0.5) * self.size.y);
+            let position = [
+                center.x + offset.x * cos - offset.y * sin,
+                center.y + offset.x * sin + offset.y * cos,
+            ];
+            let uv = [
+                lerp(region.uv_min[0], region.uv_max[0], lerp(u_min, u_max, x)),
+                lerp(region.uv_min[1], region.uv_max[1], lerp(v_min, v_max, y)),
+            ];
+            Vertex::new(position, uv, self.transparency)
+        };
 
-        Self {
-            shader_program,
-            view,
-            vertex_buffer,
-        }
+        [
+            vertex(0.0, 1.0),
+            vertex(1.0, 0.0),
+            vertex(0.0, 0.0),
+            vertex(0.0, 1.0),
+            vertex(1.0, 1.0),
+            vertex(1.0, 0.0),
+        ]
     }
+}
 
-    pub fn viewport_resized(&mut self, (width, height): (u32, u32)) {
-        log::debug!("Viewport resized to ({width:}, {height:})");
-        self.view = glm::ortho(0.0, width as f32, height as f32, 0.0, -1.0, 1.0);
+/// Sprites of one frame in drawing order, drawn by [`SpriteRenderer::draw`] all at once
+#[derive(Debug, Default)]
+pub struct SpriteBatch {
+    quads: Vec<Quad>,
+}
+
+impl SpriteBatch {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    pub fn render(
-        &self,
-        frame: &mut Frame,
+    pub fn clear(&mut self) {
+        self.quads.clear();
+    }
+
+    pub fn push(
+        &mut self,
         texture: &Texture,
         RenderOptions {
             position,
             size,
             rotation,
             pan,
-            flip_horizontal,
             flip_vertical,
+            flip_horizontal,
+            transparency,
         }: RenderOptions,
     ) {
-        let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+        let quad = Quad {
+            texture: *texture,
+            position,
+            size,
+            rotation,
+            uv_min: [0.0, 0.0],
+            uv_max: [1.0, 1.0],
+            flip_vertical,
+            flip_horizontal,
+            transparency,
+        };
 
-        let mut model = glm::identity::<f32, 4>();
-        model = glm::translate(&model, &glm::vec3(position.x, position.y, 0.0));
+        // A scrolled texture wraps around, which takes a second quad for the part that wrapped
+        let start = pan.rem_euclid(1.0);
+        if start == 0.0 {
+            self.quads.push(quad);
+            return;
+        }
+        let split = size.x * (1.0 - start);
+        self.quads.push(Quad {
+            size: glm::vec2(split, size.y),
+            uv_min: [start, 0.0],
+            ..quad
+        });
+        self.quads.push(Quad {
+            position: glm::vec2(position.x + split, position.y),
+            size: glm::vec2(size.x - split, size.y),
+            uv_max: [start, 1.0],
+            ..quad
+        });
+    }
 
-        model = glm::translate(&model, &glm::vec3(0.5 * size.x, 0.5 * size.y, 0.0));
-        model = glm::rotate(
-            &model,
-            rotation * f32::pi() / 180.0,
-            &glm::vec3(0.0, 0.0, 1.0),
-        );
-        model = glm::translate(&model, &glm::vec3(-0.5 * size.x, -0.5 * size.y, 0.0));
+    /// Add a quad as is, for sprites [`SpriteBatch::push`] has no options for
+    pub fn push_quad(&mut self, quad: Quad) {
+        self.quads.push(quad);
+    }
+
+    pub fn quads(&self) -> &[Quad] {
+        &self.quads
+    }
+
+    pub fn len(&self) -> usize {
+        self.quads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quads.is_empty()
+    }
+}
 
-        let model = glm::scale(&model, &glm::vec3(size.x, size.y, 1.0));
-        let model_ref = model.as_ref();
-        let projection_ref = self.view.as_ref();
+/// Maps the fixed size world onto the window, keeping its aspect ratio
+///
+/// The world is scaled as large as fits and centered, leaving bars on the sides or on
+/// top and bottom.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Camera {
+    world_size: (u32, u32),
+    /// Round the scale down to a whole number if the world still fits at least once
+    integer_scale: bool,
+    /// Part of the framebuffer the world is drawn to
+    viewport: Rect,
+}
+
+impl Camera {
+    pub fn new(world_size: (u32, u32), framebuffer_size: (u32, u32), integer_scale: bool) -> Self {
+        let mut camera = Self {
+            world_size,
+            integer_scale,
+            viewport: Rect {
+                left: 0,
+                bottom: 0,
+                width: world_size.0,
+                height: world_size.1,
+            },
+        };
+        camera.resize(framebuffer_size);
+        camera
+    }
+
+    pub fn resize(&mut self, (width, height): (u32, u32)) {
+        // Minimized windows report a size of 0, keep drawing as before until restored
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let (world_width, world_height) = (self.world_size.0 as f32, self.world_size.1 as f32);
+        let mut scale = (width as f32 / world_width).min(height as f32 / world_height);
+        if self.integer_scale && scale >= 1.0 {
+            scale = scale.floor();
+        }
+        let scaled_width = ((world_width * scale).round() as u32).min(width);
+        let scaled_height = ((world_height * scale).round() as u32).min(height);
+
+        self.viewport = Rect {
+            left: (width - scaled_width) / 2,
+            bottom: (height - scaled_height) / 2,
+            width: scaled_width,
+            height: scaled_height,
+        };
+    }
+
+    pub fn viewport(&self) -> Rect {
+        self.viewport
+    }
+
+    /// Framebuffer pixels per world pixel
+    pub fn scale(&self) -> f32 {
+        self.viewport.width as f32 / self.world_size.0 as f32
+    }
+
+    pub fn projection(&self) -> na::Matrix4<f32> {
+        let (width, height) = self.world_size;
+        glm::ortho(0.0, width as f32, height as f32, 0.0, -1.0, 1.0)
+    }
+}
+
+pub struct SpriteRenderer {
+    context: Rc<Context>,
+    camera: Camera,
+    view: na::Matrix4<f32>,
+    shader_program: Program,
+    atlas: Atlas,
+    /// Reused between frames and only grown when a batch doesn't fit
+    vertex_buffer: VertexBuffer<Vertex>,
+    /// `frame.draw` calls made so far
+    draw_calls: usize,
+}
 
-        // We are flipping about the axis so use opposite here
-        let flip: TVec2<f32> = glm::vec2(
-            if flip_horizontal { 1.0 } else { 0.0 },
-            if flip_vertical { 1.0 } else { 0.0 },
+impl SpriteRenderer {
+    /// Renderer drawing a world of `world_size` pixels scaled to fit the framebuffer
+    pub fn new(
+        facade: &impl Facade,
+        atlas: PackedAtlas,
+        world_size: (u32, u32),
+        integer_scale: bool,
+    ) -> Self {
+        let context = facade.get_context().clone();
+        let shader_program = load_shader(facade);
+        let camera = Camera::new(
+            world_size,
+            context.get_framebuffer_dimensions(),
+            integer_scale,
         );
+        let view = camera.projection();
+        let atlas = atlas.upload(facade);
+        let vertex_buffer = VertexBuffer::empty_dynamic(facade, 6 * 64).unwrap();
 
-        let sampler = texture
+        Self {
+            context,
+            camera,
+            view,
+            shader_program,
+            atlas,
+            vertex_buffer,
+            draw_calls: 0,
+        }
+    }
+
+    pub fn viewport_resized(&mut self, (width, height): (u32, u32)) {
+        log::debug!("Viewport resized to ({width:}, {height:})");
+        self.camera.resize((width, height));
+    }
+
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// Draw calls issued since the renderer was created
+    pub fn draw_calls(&self) -> usize {
+        self.draw_calls
+    }
+
+    /// Draw every sprite in `batch` with a single draw call
+    pub fn draw(&mut self, target: &mut impl Surface, batch: &SpriteBatch) {
+        if batch.is_empty() {
+            return;
+        }
+
+        let vertices: Vec<Vertex> = batch
+            .quads()
+            .iter()
+            .flat_map(|quad| quad.vertices(self.atlas.region(&quad.texture)))
+            .collect();
+        if vertices.len() > self.vertex_buffer.len() {
+            self.vertex_buffer =
+                VertexBuffer::empty_dynamic(&self.context, vertices.len().next_power_of_two())
+                    .unwrap();
+        }
+        let vertex_buffer = self.vertex_buffer.slice(0..vertices.len()).unwrap();
+        vertex_buffer.write(&vertices);
+
+        let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+        let sampler = self
+            .atlas
             .texture
             .sampled()
-            .magnify_filter(glium::uniforms::MagnifySamplerFilter::Nearest);
+            .magnify_filter(MagnifySamplerFilter::Nearest)
+            .minify_filter(MinifySamplerFilter::Nearest)
+            .wrap_function(SamplerWrapFunction::Clamp);
+        let uniforms = uniform! { sprite: sampler, projection: *self.view.as_ref() };
+        let draw_parameters = glium::DrawParameters {
+            blend: glium::Blend::alpha_blending(),
+            viewport: Some(self.camera.viewport()),
+            ..Default::default()
+        };
 
-        let uniforms = uniform! { sprite: sampler, model: *model_ref, projection: *projection_ref, pan: *pan.as_ref(), flip: *flip.as_ref() };
-        frame
+        target
             .draw(
-                &self.vertex_buffer,
+                vertex_buffer,
                 indices,
                 &self.shader_program,
                 &uniforms,
-                &Default::default(),
+                &draw_parameters,
             )
             .unwrap();
+        self.draw_calls += 1;
     }
 }
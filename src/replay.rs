@@ -0,0 +1,138 @@
+//! Recording runs and replaying them deterministically
+//!
+//! A replay stores everything that feeds into the simulation: seed, viewport, config,
+//! bird color and the tick of every flap. Replaying feeds those flaps back into a fresh
+//! [`GameWorld`] and checks that it ends the same way.
+
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, ConfigError};
+use crate::gamestate::{PlayState, Score};
+use crate::world::bird::BirdColor;
+use crate::world::{CollisionMode, GameWorld, SIMULATION_STEP};
+
+/// Bump whenever the on-disk layout of [`Replay`] or the simulation it replays changes
+const FORMAT_VERSION: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    #[serde(with = "crate::util::u64_as_string")]
+    pub seed: u64,
+    pub viewport_size: (u32, u32),
+    pub hit_detection: bool,
+    #[serde(default)]
+    pub collision_mode: CollisionMode,
+    /// Pixel collisions use the mask of the bird's color
+    pub bird_color: BirdColor,
+    /// Ticks at which the bird flapped, in order
+    pub flaps: Vec<u64>,
+    pub final_score: Score,
+    /// Length of the run in ticks
+    pub ticks: u64,
+    pub config: Config,
+}
+
+/// How a replayed run ended
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ReplayOutcome {
+    pub score: Score,
+    pub ticks: u64,
+    /// Whether the bird died, recordings are always of runs that ended
+    pub game_over: bool,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+    UnsupportedVersion(u32),
+    Config(ConfigError),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "{err}"),
+            ReplayError::Parse(err) => write!(f, "invalid replay: {err}"),
+            ReplayError::Serialize(err) => write!(f, "could not serialize replay: {err}"),
+            ReplayError::UnsupportedVersion(version) => {
+                write!(f, "unsupported replay version {version}")
+            }
+            ReplayError::Config(err) => write!(f, "invalid replay config: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl Replay {
+    /// Capture the run that just ended in `world`
+    ///
+    /// The config and bird color are the ones the run started with, changing them during
+    /// the run desyncs the replay.
+    pub fn capture(world: &GameWorld) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            seed: world.state.seed,
+            viewport_size: world.state.viewport_size,
+            hit_detection: world.hit_detection,
+            collision_mode: world.collision_mode,
+            bird_color: world.run_bird_color(),
+            flaps: world.flap_ticks().to_vec(),
+            final_score: world.state.score,
+            ticks: world.state.tick,
+            config: world.run_config().clone(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        let contents = std::fs::read_to_string(path).map_err(ReplayError::Io)?;
+        let replay: Self = toml::from_str(&contents).map_err(ReplayError::Parse)?;
+        if replay.version != FORMAT_VERSION {
+            return Err(ReplayError::UnsupportedVersion(replay.version));
+        }
+        replay.config.validate().map_err(ReplayError::Config)?;
+
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        let contents = toml::to_string(self).map_err(ReplayError::Serialize)?;
+        std::fs::write(path, contents).map_err(ReplayError::Io)
+    }
+
+    /// Re-run the recorded inputs in a fresh world
+    ///
+    /// The run stops when the bird dies or once it is as long as the recording.
+    pub fn run(&self) -> ReplayOutcome {
+        let mut world = GameWorld::new(self.viewport_size, self.seed, self.config.clone());
+        world.hit_detection = self.hit_detection;
+        world.collision_mode = self.collision_mode;
+        world.bird.color = self.bird_color;
+        world.start(self.seed);
+
+        let mut flaps = self.flaps.iter().peekable();
+        while !matches!(world.state.state, PlayState::GameOver) && world.state.tick < self.ticks {
+            if flaps.next_if_eq(&&world.state.tick).is_some() {
+                world.flap();
+            }
+            world.step(SIMULATION_STEP);
+        }
+
+        ReplayOutcome {
+            score: world.state.score,
+            ticks: world.state.tick,
+            game_over: matches!(world.state.state, PlayState::GameOver),
+        }
+    }
+
+    /// Whether `outcome` matches how the recorded run ended
+    pub fn matches(&self, outcome: &ReplayOutcome) -> bool {
+        outcome.game_over && outcome.score == self.final_score && outcome.ticks == self.ticks
+    }
+}
@@ -1,10 +1,8 @@
-use glium::glutin::surface::WindowSurface;
-use glium::Display;
+use glium::backend::Facade;
 
 const VERTEX_SHADER_SOURCE: &str = include_str!("../sprite_vertex.vert");
 const FRAGMENT_SHADER_SOURCE: &str = include_str!("../sprite_fragment.frag");
 
-pub fn load_shader(display: &Display<WindowSurface>) -> glium::Program {
-    glium::Program::from_source(display, VERTEX_SHADER_SOURCE, FRAGMENT_SHADER_SOURCE, None)
-        .unwrap()
+pub fn load_shader(facade: &impl Facade) -> glium::Program {
+    glium::Program::from_source(facade, VERTEX_SHADER_SOURCE, FRAGMENT_SHADER_SOURCE, None).unwrap()
 }
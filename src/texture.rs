@@ -1,20 +1,25 @@
-use glium::glutin::surface::WindowSurface;
-use glium::Display;
+use crate::atlas::AtlasBuilder;
 
+/// Handle to a sprite in the texture atlas
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Texture {
-    pub texture: glium::texture::SrgbTexture2d,
+    /// Position of the sprite in the atlas regions
+    pub(crate) index: usize,
     pub size: (u32, u32),
 }
 
 impl Texture {
-    pub fn from_bytes(bytes: &[u8], display: &Display<WindowSurface>) -> Self {
+    pub fn from_bytes(bytes: &[u8], atlas: &mut AtlasBuilder) -> Self {
         let image = image::load(std::io::Cursor::new(bytes), image::ImageFormat::Png)
             .unwrap()
             .to_rgba8();
-        let size = image.dimensions();
-        let image = glium::texture::RawImage2d::from_raw_rgba(image.into_raw(), size);
-        let texture = glium::texture::SrgbTexture2d::new(display, image).unwrap();
+        atlas.add(image)
+    }
 
-        Self { texture, size }
+    /// Texture from raw RGBA8 pixels, row by row from the top
+    pub fn from_rgba(pixels: Vec<u8>, size: (u32, u32), atlas: &mut AtlasBuilder) -> Self {
+        let image = image::RgbaImage::from_raw(size.0, size.1, pixels)
+            .expect("pixel count has to match the size");
+        atlas.add(image)
     }
 }
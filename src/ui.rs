@@ -1,37 +1,162 @@
-use glium::glutin::surface::WindowSurface;
-use glium::{Display, Frame};
+use nalgebra as na;
 use nalgebra_glm as glm;
 
-use crate::gamestate::{GameState, PlayState};
-use crate::renderer::{Render, RenderOptions, SpriteRenderer};
+use crate::atlas::AtlasBuilder;
+use crate::gamestate::{PlayState, Score};
+use crate::renderer::{Render, RenderOptions, SpriteBatch};
 use crate::texture::Texture;
 use crate::util::{horizontally_centered_position, vertically_centered_position};
+use crate::world::GameWorld;
+
+/// Seconds the screen flashes white when the bird hits something
+const FLASH_DURATION: f32 = 0.2;
+/// How much of the game shows through the overlay while paused
+const PAUSE_TRANSPARENCY: f32 = 0.5;
+/// "BEST" in the blocky style of the digit sprites, `#` is filled
+const BEST_LABEL: [&str; 7] = [
+    "####  #####  #### #####",
+    "#   # #     #       #  ",
+    "#   # #     #       #  ",
+    "####  ####   ###    #  ",
+    "#   # #         #   #  ",
+    "#   # #         #   #  ",
+    "####  ##### ####    #  ",
+];
+/// Screen pixels per label pixel, which makes the outlined label as tall as a digit
+const LABEL_SCALE: f32 = 4.0;
 
 pub struct Ui {
     begin_texture: Texture,
-    // score_textures: [Texture; 10],
+    score_textures: [Texture; 10],
     gameover_texture: Texture,
+    best_texture: Texture,
+    white_texture: Texture,
+    black_texture: Texture,
 }
 
 impl Ui {
-    pub fn new(display: &Display<WindowSurface>) -> Self {
+    pub fn new(atlas: &mut AtlasBuilder) -> Self {
         let begin_texture =
-            Texture::from_bytes(include_bytes!("../assets/sprites/message.png"), display);
+            Texture::from_bytes(include_bytes!("../assets/sprites/message.png"), atlas);
+        let score_textures = [
+            Texture::from_bytes(include_bytes!("../assets/sprites/0.png"), atlas),
+            Texture::from_bytes(include_bytes!("../assets/sprites/1.png"), atlas),
+            Texture::from_bytes(include_bytes!("../assets/sprites/2.png"), atlas),
+            Texture::from_bytes(include_bytes!("../assets/sprites/3.png"), atlas),
+            Texture::from_bytes(include_bytes!("../assets/sprites/4.png"), atlas),
+            Texture::from_bytes(include_bytes!("../assets/sprites/5.png"), atlas),
+            Texture::from_bytes(include_bytes!("../assets/sprites/6.png"), atlas),
+            Texture::from_bytes(include_bytes!("../assets/sprites/7.png"), atlas),
+            Texture::from_bytes(include_bytes!("../assets/sprites/8.png"), atlas),
+            Texture::from_bytes(include_bytes!("../assets/sprites/9.png"), atlas),
+        ];
         let gameover_texture =
-            Texture::from_bytes(include_bytes!("../assets/sprites/gameover.png"), display);
+            Texture::from_bytes(include_bytes!("../assets/sprites/gameover.png"), atlas);
+
+        let best_texture = label_texture(&BEST_LABEL, atlas);
+        let white_texture = Texture::from_rgba(vec![255; 4], (1, 1), atlas);
+        let black_texture = Texture::from_rgba(vec![0, 0, 0, 255], (1, 1), atlas);
 
         Self {
             begin_texture,
+            score_textures,
             gameover_texture,
+            best_texture,
+            white_texture,
+            black_texture,
+        }
+    }
+
+    fn digits(&self, score: Score) -> Vec<&Texture> {
+        score
+            .to_string()
+            .bytes()
+            .map(|digit| &self.score_textures[(digit - b'0') as usize])
+            .collect()
+    }
+
+    /// Width of `score` drawn at `scale`
+    fn score_width(&self, score: Score, scale: f32) -> f32 {
+        self.digits(score)
+            .iter()
+            .map(|texture| texture.size.0 as f32)
+            .sum::<f32>()
+            * scale
+    }
+
+    /// Draw `score` horizontally centered in the viewport with its top edge at `y`
+    fn render_score(
+        &self,
+        batch: &mut SpriteBatch,
+        viewport_size: na::Vector2<f32>,
+        score: Score,
+        y: f32,
+        scale: f32,
+    ) {
+        let width = self.score_width(score, scale);
+        let height = self.score_textures[0].size.1 as f32 * scale;
+        let x = vertically_centered_position(viewport_size, glm::vec2(width, height));
+        self.render_score_at(batch, score, glm::vec2(x, y), scale);
+    }
+
+    /// Draw `score` with its top left corner at `position`
+    fn render_score_at(
+        &self,
+        batch: &mut SpriteBatch,
+        score: Score,
+        position: na::Vector2<f32>,
+        scale: f32,
+    ) {
+        let mut x = position.x;
+        for texture in self.digits(score) {
+            let size = glm::vec2(texture.size.0 as f32, texture.size.1 as f32) * scale;
+            batch.push(
+                texture,
+                RenderOptions {
+                    position: glm::vec2(x, position.y),
+                    size,
+                    ..RenderOptions::default()
+                },
+            );
+            x += size.x;
         }
     }
+
+    /// Draw the best score behind a "BEST" label, horizontally centered with its top at `y`
+    fn render_best_score(
+        &self,
+        batch: &mut SpriteBatch,
+        viewport_size: na::Vector2<f32>,
+        score: Score,
+        y: f32,
+    ) {
+        let label_size = glm::vec2(
+            self.best_texture.size.0 as f32,
+            self.best_texture.size.1 as f32,
+        ) * LABEL_SCALE;
+        let gap = LABEL_SCALE * 3.0;
+        let width = label_size.x + gap + self.score_width(score, 1.0);
+        let height = self.score_textures[0].size.1 as f32;
+        let x = vertically_centered_position(viewport_size, glm::vec2(width, height));
+
+        batch.push(
+            &self.best_texture,
+            RenderOptions {
+                position: glm::vec2(x, y + (height - label_size.y) * 0.5),
+                size: label_size,
+                ..RenderOptions::default()
+            },
+        );
+        self.render_score_at(batch, score, glm::vec2(x + label_size.x + gap, y), 1.0);
+    }
 }
 
 impl Render for Ui {
-    fn render(&self, frame: &mut Frame, renderer: &SpriteRenderer, game_state: &GameState) {
+    fn render(&self, batch: &mut SpriteBatch, world: &GameWorld) {
+        let game_state = &world.state;
         let viewport_size = game_state.viewport_size;
         let viewport_size = glm::vec2(viewport_size.0 as f32, viewport_size.1 as f32);
-        match game_state.state {
+        match world.unpaused_state() {
             PlayState::MainMenu => {
                 let texture_size = self.begin_texture.size;
                 let size = glm::vec2(texture_size.0 as f32, texture_size.1 as f32) * 2.0;
@@ -40,8 +165,7 @@ impl Render for Ui {
                     horizontally_centered_position(viewport_size, size),
                 );
 
-                renderer.render(
-                    frame,
+                batch.push(
                     &self.begin_texture,
                     RenderOptions {
                         position,
@@ -50,7 +174,15 @@ impl Render for Ui {
                     },
                 );
             }
-            PlayState::Playing => {}
+            PlayState::Ready | PlayState::Playing | PlayState::Dying => {
+                self.render_score(
+                    batch,
+                    viewport_size,
+                    game_state.score,
+                    viewport_size.y * 0.08,
+                    1.5,
+                );
+            }
             PlayState::GameOver => {
                 let texture_size = self.gameover_texture.size;
                 let size = glm::vec2(texture_size.0 as f32, texture_size.1 as f32) * 2.0;
@@ -59,8 +191,7 @@ impl Render for Ui {
                     horizontally_centered_position(viewport_size, size),
                 );
 
-                renderer.render(
-                    frame,
+                batch.push(
                     &self.gameover_texture,
                     RenderOptions {
                         position,
@@ -68,7 +199,68 @@ impl Render for Ui {
                         ..RenderOptions::default()
                     },
                 );
+
+                // Final score below the banner, labelled best score smaller underneath it
+                let score_y = position.y + size.y + viewport_size.y * 0.03;
+                self.render_score(batch, viewport_size, game_state.score, score_y, 2.5);
+                let best_y = score_y + self.score_textures[0].size.1 as f32 * 2.5 + 10.0;
+                self.render_best_score(batch, viewport_size, game_state.best_score, best_y);
             }
+            PlayState::Paused => {}
         };
+
+        // Flash that fades out right after the hit, which may already have ended the run
+        if let Some(elapsed) = game_state.time_since_hit {
+            let elapsed = elapsed.as_secs_f32();
+            if elapsed < FLASH_DURATION {
+                batch.push(
+                    &self.white_texture,
+                    RenderOptions {
+                        size: viewport_size,
+                        transparency: elapsed / FLASH_DURATION,
+                        ..RenderOptions::default()
+                    },
+                );
+            }
+        }
+
+        // Dim whatever was on screen when the game got paused
+        if matches!(game_state.state, PlayState::Paused) {
+            batch.push(
+                &self.black_texture,
+                RenderOptions {
+                    size: viewport_size,
+                    transparency: PAUSE_TRANSPARENCY,
+                    ..RenderOptions::default()
+                },
+            );
+        }
+    }
+}
+
+/// White pixel art label from `rows` of `#` and spaces, outlined in black like the digits
+fn label_texture(rows: &[&str], atlas: &mut AtlasBuilder) -> Texture {
+    let filled = |x: i32, y: i32| {
+        usize::try_from(y)
+            .ok()
+            .and_then(|y| rows.get(y))
+            .zip(usize::try_from(x).ok())
+            .is_some_and(|(row, x)| row.as_bytes().get(x) == Some(&b'#'))
+    };
+
+    // A pixel of outline all around
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0) as i32 + 2;
+    let height = rows.len() as i32 + 2;
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for y in -1..height - 1 {
+        for x in -1..width - 1 {
+            let outline = (-1..=1).any(|dy| (-1..=1).any(|dx| filled(x + dx, y + dy)));
+            pixels.extend(match (filled(x, y), outline) {
+                (true, _) => [255, 255, 255, 255],
+                (false, true) => [0, 0, 0, 255],
+                (false, false) => [0, 0, 0, 0],
+            });
+        }
     }
+    Texture::from_rgba(pixels, (width as u32, height as u32), atlas)
 }
@@ -1,5 +1,9 @@
 use nalgebra as na;
 
+pub fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
 pub fn vertically_centered_position(
     container_size: na::Vector2<f32>,
     element_size: na::Vector2<f32>,
@@ -13,3 +17,18 @@ pub fn horizontally_centered_position(
 ) -> f32 {
     container_size.y / 2.0 - (element_size.y / 2.0)
 }
+
+/// Serde helpers storing a `u64` as a decimal string, TOML integers only go up to `i64::MAX`
+pub mod u64_as_string {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
@@ -4,23 +4,17 @@ use glium::implement_vertex;
 pub struct Vertex {
     position: [f32; 2],
     uv: [f32; 2],
+    transparency: f32,
 }
 
 impl Vertex {
-    fn new(position: [f32; 2], uv: [f32; 2]) -> Self {
-        Self { position, uv }
-    }
-
-    pub fn sprite_rectangle() -> [Vertex; 6] {
-        [
-            Vertex::new([0.0, 1.0], [0.0, 1.0]),
-            Vertex::new([1.0, 0.0], [1.0, 0.0]),
-            Vertex::new([0.0, 0.0], [0.0, 0.0]),
-            Vertex::new([0.0, 1.0], [0.0, 1.0]),
-            Vertex::new([1.0, 1.0], [1.0, 1.0]),
-            Vertex::new([1.0, 0.0], [1.0, 0.0]),
-        ]
+    pub fn new(position: [f32; 2], uv: [f32; 2], transparency: f32) -> Self {
+        Self {
+            position,
+            uv,
+            transparency,
+        }
     }
 }
 
-implement_vertex!(Vertex, position, uv);
+implement_vertex!(Vertex, position, uv, transparency);
@@ -0,0 +1,57 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::gamestate::{GameState, PlayState, Update};
+use crate::util::lerp;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextureVariant {
+    Day,
+    Night,
+}
+
+impl FromStr for TextureVariant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "day" => Ok(TextureVariant::Day),
+            "night" => Ok(TextureVariant::Night),
+            _ => Err(format!("unknown background `{s}`, expected day or night")),
+        }
+    }
+}
+
+pub struct Background {
+    offset: f32,
+    previous_offset: f32,
+    speed: f32,
+    pub texture_variant: TextureVariant,
+}
+
+impl Background {
+    pub fn new(speed: f32) -> Self {
+        Self {
+            offset: 0.0,
+            previous_offset: 0.0,
+            speed,
+            texture_variant: TextureVariant::Night,
+        }
+    }
+
+    pub fn interpolated_offset(&self, t: f32) -> f32 {
+        lerp(self.previous_offset, self.offset, t)
+    }
+}
+
+impl Update for Background {
+    fn update(&mut self, dt: Duration, game_state: &mut GameState) {
+        self.previous_offset = self.offset;
+        if matches!(game_state.state, PlayState::Playing) {
+            self.offset += dt.as_secs_f32() * self.speed * game_state.difficulty.speed_multiplier;
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::f32::consts::TAU;
+use std::str::FromStr;
+use std::time::Duration;
+
+use nalgebra as na;
+use nalgebra_glm as glm;
+use serde::{Deserialize, Serialize};
+
+use crate::config::BirdConfig;
+use crate::gamestate::{
+    BoundingBox, Collider, GameState, Hittable, OrientedBoundingBox, PlayState, Update,
+};
+use crate::util::lerp;
+use crate::world::ground::GROUND_HEIGHT_PERCENT;
+
+/// Size of a single bird sprite in pixels, all colors and flaps share it
+const SPRITE_SIZE: (f32, f32) = (34.0, 24.0);
+const SPRITE_SCALE: f32 = 1.5;
+
+#[derive(Debug, Copy, Clone)]
+pub enum Flap {
+    Down = 0,
+    Mid = 1,
+    Up = 2,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BirdColor {
+    Blue = 0,
+    Red = 1,
+    Yellow = 2,
+}
+
+impl FromStr for BirdColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "blue" => Ok(BirdColor::Blue),
+            "red" => Ok(BirdColor::Red),
+            "yellow" => Ok(BirdColor::Yellow),
+            _ => Err(format!(
+                "unknown bird color `{s}`, expected blue, red or yellow"
+            )),
+        }
+    }
+}
+
+/// Pixels the bird moves up and down while getting ready
+const BOB_AMPLITUDE: f32 = 8.0;
+/// Bobs per second while getting ready
+const BOB_FREQUENCY: f32 = 1.5;
+
+const FLAP_CYCLE: [Flap; 4] = [Flap::Down, Flap::Mid, Flap::Up, Flap::Mid];
+
+pub struct Bird {
+    pub color: BirdColor,
+
+    pub y_position: f32,
+    previous_y_position: f32,
+    pub y_velocity: f32,
+    flap_index: usize,
+    flap_duration: Duration,
+    pub rotation: f32,
+
+    pub gravity: f32,
+    pub upwards_force: f32,
+    pub flap_frame_duration: f32,
+    pub min_rotation: f32,
+    pub max_rotation: f32,
+    pub rotation_speed: f32,
+}
+
+impl Bird {
+    pub fn new(game_state: &GameState, config: BirdConfig) -> Self {
+        let y_position = game_state.viewport_size.1 as f32 * 0.50;
+
+        Self {
+            color: BirdColor::Blue,
+            y_position,
+            previous_y_position: y_position,
+            y_velocity: 0.0,
+            flap_index: 0,
+            flap_duration: Duration::from_secs_f32(0.0),
+            rotation: 0.0,
+            gravity: config.gravity,
+            upwards_force: config.upwards_force,
+            flap_frame_duration: config.flap_frame_duration,
+            min_rotation: config.min_rotation,
+            max_rotation: config.max_rotation,
+            rotation_speed: config.rotation_speed,
+        }
+    }
+
+    pub fn reset(&mut self, game_state: &GameState) {
+        let height = game_state.viewport_size.1 as f32;
+        self.y_position = height * 0.50;
+        self.previous_y_position = self.y_position;
+        self.y_velocity = 0.0;
+        self.flap_index = 0;
+        self.flap_duration = Duration::from_secs_f32(0.0);
+        self.rotation = 0.0;
+    }
+
+    /// Current frame of the flap animation
+    pub fn flap(&self) -> Flap {
+        FLAP_CYCLE[self.flap_index]
+    }
+
+    fn fall(&mut self, dt: Duration) {
+        self.y_velocity += self.gravity * dt.as_secs_f32();
+        self.y_position += self.y_velocity * dt.as_secs_f32();
+
+        // Hold the nose up while rising, then pitch down towards a nosedive
+        if self.y_velocity > 0.0 {
+            self.rotation =
+                (self.rotation + self.rotation_speed * dt.as_secs_f32()).min(self.max_rotation);
+        }
+    }
+
+    /// `y_position` at which the bird sits on the ground
+    fn resting_position(game_state: &GameState) -> f32 {
+        let ground_top = game_state.viewport_size.1 as f32 * (1.0 - GROUND_HEIGHT_PERCENT);
+        ground_top - SPRITE_SIZE.1 * SPRITE_SCALE
+    }
+
+    pub fn is_on_ground(&self, game_state: &GameState) -> bool {
+        self.y_position >= Self::resting_position(game_state)
+    }
+
+    pub fn is_nosediving(&self) -> bool {
+        self.rotation >= self.max_rotation
+    }
+
+    pub fn interpolated_y_position(&self, t: f32) -> f32 {
+        lerp(self.previous_y_position, self.y_position, t)
+    }
+}
+
+impl Update for Bird {
+    fn update(&mut self, dt: Duration, game_state: &mut GameState) {
+        self.previous_y_position = self.y_position;
+        match game_state.state {
+            PlayState::Ready => {
+                let phase = game_state.time_in_state.as_secs_f32() * BOB_FREQUENCY * TAU;
+                self.y_position =
+                    game_state.viewport_size.1 as f32 * 0.50 + BOB_AMPLITUDE * phase.sin();
+            }
+            PlayState::Playing if game_state.fly_up => {
+                self.y_velocity = -self.upwards_force;
+                self.rotation = self.min_rotation;
+            }
+            PlayState::Playing => self.fall(dt),
+            PlayState::Dying => {
+                self.fall(dt);
+
+                // Come to rest on top of the ground
+                let resting_position = Self::resting_position(game_state);
+                if self.y_position >= resting_position {
+                    self.y_position = resting_position;
+                    self.y_velocity = 0.0;
+                }
+            }
+            _ => {}
+        }
+
+        // Wings stay still while nosediving
+        if !self.is_nosediving() {
+            self.flap_duration += dt;
+        }
+        let flap_frame_duration = Duration::from_secs_f32(self.flap_frame_duration);
+        if self.flap_duration >= flap_frame_duration {
+            self.flap_index = (self.flap_index + 1) % 4;
+            self.flap_duration -= flap_frame_duration;
+        }
+
+        game_state.bird_bounding_box = self.bounding_boxes(game_state)[0];
+    }
+}
+
+impl Hittable for Bird {
+    fn bounding_boxes(&self, game_state: &GameState) -> Vec<BoundingBox> {
+        let size = na::Vector2::new(SPRITE_SIZE.0, SPRITE_SIZE.1) * SPRITE_SCALE;
+        let position = glm::vec2(game_state.viewport_size.0 as f32 * 0.25, self.y_position);
+
+        vec![BoundingBox { position, size }]
+    }
+
+    fn colliders(&self, game_state: &GameState) -> Vec<Collider> {
+        // The sprite is drawn rotated about its center
+        let bounding_box = self.bounding_boxes(game_state)[0];
+        vec![Collider::Oriented(OrientedBoundingBox::from_bounding_box(
+            &bounding_box,
+            self.rotation,
+        ))]
+    }
+}
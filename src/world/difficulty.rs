@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::{DifficultyConfig, PipesConfig};
+use crate::gamestate::Score;
+use crate::world::pipes::MAX_APERTURE_PERCENT;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CurvePoint {
+    pub score: Score,
+    pub value: f32,
+}
+
+/// Piecewise linear function of the score, flat before the first and after the last point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Curve(pub Vec<CurvePoint>);
+
+impl Curve {
+    pub fn new(points: &[(Score, f32)]) -> Self {
+        Self(
+            points
+                .iter()
+                .map(|&(score, value)| CurvePoint { score, value })
+                .collect(),
+        )
+    }
+
+    /// Value at `score`, points have to be sorted by score
+    pub fn at(&self, score: Score) -> f32 {
+        let points = &self.0;
+        let Some(next) = points.iter().position(|point| point.score > score) else {
+            return points.last().map_or(1.0, |point| point.value);
+        };
+        if next == 0 {
+            return points[0].value;
+        }
+
+        let (a, b) = (points[next - 1], points[next]);
+        let t = (score - a.score) as f32 / (b.score - a.score) as f32;
+        a.value + (b.value - a.value) * t
+    }
+
+    pub fn points(&self) -> &[CurvePoint] {
+        &self.0
+    }
+}
+
+/// How hard the game currently is, derived from the score of the run
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Difficulty {
+    /// Factor applied to the scroll speed of pipes, ground and background alike
+    pub speed_multiplier: f32,
+    /// Height of the opening of newly spawned pipe pairs as a fraction of the viewport height
+    pub aperture_percent: f32,
+    /// Fraction the space before a newly spawned pair may randomly differ from the configured one
+    pub spacing_variation: f32,
+}
+
+impl Difficulty {
+    pub fn at(config: &DifficultyConfig, pipes: &PipesConfig, score: Score) -> Self {
+        // Never widen apertures that are configured smaller than the minimum, and never
+        // open them so wide that no pipe is left to place
+        let min_aperture = config.min_aperture_percent.min(pipes.aperture_percent);
+
+        Self {
+            speed_multiplier: config.speed.at(score).min(config.max_speed_multiplier),
+            aperture_percent: (pipes.aperture_percent * config.aperture.at(score))
+                .max(min_aperture)
+                .min(MAX_APERTURE_PERCENT),
+            spacing_variation: config
+                .spacing_variation
+                .at(score)
+                .min(config.max_spacing_variation),
+        }
+    }
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self {
+            speed_multiplier: 1.0,
+            aperture_percent: PipesConfig::default().aperture_percent,
+            spacing_variation: 0.0,
+        }
+    }
+}
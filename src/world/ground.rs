@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use nalgebra_glm as glm;
+
+use crate::gamestate::{BoundingBox, GameState, Hittable, PlayState, Update};
+use crate::util::lerp;
+
+/// Fraction of the viewport height covered by the ground
+pub const GROUND_HEIGHT_PERCENT: f32 = 0.20;
+
+pub struct Ground {
+    offset: f32,
+    previous_offset: f32,
+    speed: f32,
+}
+
+impl Ground {
+    pub fn new(speed: f32) -> Self {
+        Self {
+            offset: 0.0,
+            previous_offset: 0.0,
+            speed,
+        }
+    }
+
+    pub fn interpolated_offset(&self, t: f32) -> f32 {
+        lerp(self.previous_offset, self.offset, t)
+    }
+}
+
+impl Update for Ground {
+    fn update(&mut self, dt: Duration, game_state: &mut GameState) {
+        self.previous_offset = self.offset;
+        if matches!(game_state.state, PlayState::Ready | PlayState::Playing) {
+            self.offset += dt.as_secs_f32() * self.speed * game_state.difficulty.speed_multiplier;
+        }
+    }
+}
+
+impl Hittable for Ground {
+    fn bounding_boxes(&self, game_state: &GameState) -> Vec<BoundingBox> {
+        // Cover bottom 20% of window
+        let viewport_size = game_state.viewport_size;
+        let size = glm::vec2(
+            viewport_size.0 as f32,
+            viewport_size.1 as f32 * GROUND_HEIGHT_PERCENT,
+        );
+        let position = glm::vec2(0.0, viewport_size.1 as f32 * (1.0 - GROUND_HEIGHT_PERCENT));
+        vec![BoundingBox { position, size }]
+    }
+}
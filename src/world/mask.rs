@@ -0,0 +1,177 @@
+//! Per-pixel collision from sprite alpha channels
+//!
+//! Masks are built straight from the PNGs so they work without a GPU. A texel is solid
+//! when the sprite shader would draw it, i.e. its alpha is above 0.5.
+
+use nalgebra as na;
+use nalgebra_glm as glm;
+
+use crate::gamestate::{BoundingBox, OrientedBoundingBox};
+
+/// Same threshold as the `discard` in `sprite_fragment.frag`
+const ALPHA_THRESHOLD: u8 = 127;
+/// Points sampled along each axis of every texel of the moving sprite
+const SUBSAMPLES: u32 = 2;
+
+#[derive(Debug, Clone)]
+pub struct AlphaMask {
+    width: u32,
+    height: u32,
+    solid: Vec<bool>,
+}
+
+impl AlphaMask {
+    pub fn from_png(bytes: &[u8]) -> Self {
+        let image = image::load(std::io::Cursor::new(bytes), image::ImageFormat::Png)
+            .unwrap()
+            .to_rgba8();
+        Self::from_rgba(&image)
+    }
+
+    pub fn from_rgba(image: &image::RgbaImage) -> Self {
+        let (width, height) = image.dimensions();
+        let solid = image
+            .pixels()
+            .map(|pixel| pixel.0[3] > ALPHA_THRESHOLD)
+            .collect();
+
+        Self {
+            width,
+            height,
+            solid,
+        }
+    }
+
+    pub fn is_solid(&self, x: u32, y: u32) -> bool {
+        x < self.width && y < self.height && self.solid[(y * self.width + x) as usize]
+    }
+
+    /// Whether the texel at texture coordinates `uv` is solid, `(0, 0)` being the top left
+    fn sample(&self, uv: na::Vector2<f32>) -> bool {
+        if !(0.0..1.0).contains(&uv.x) || !(0.0..1.0).contains(&uv.y) {
+            return false;
+        }
+
+        let x = (uv.x * self.width as f32) as u32;
+        let y = (uv.y * self.height as f32) as u32;
+        self.is_solid(x, y)
+    }
+}
+
+/// A mask stretched over `bounds` the way the sprite renderer draws it
+#[derive(Debug, Copy, Clone)]
+pub struct PlacedMask<'a> {
+    pub mask: &'a AlphaMask,
+    pub bounds: BoundingBox,
+    /// Clockwise rotation about the center in degrees
+    pub rotation: f32,
+    pub flip_vertical: bool,
+}
+
+impl PlacedMask<'_> {
+    /// Axis-aligned box around the mask after rotation, used as the broad phase
+    pub fn broad_phase(&self) -> BoundingBox {
+        OrientedBoundingBox::from_bounding_box(&self.bounds, self.rotation).bounds()
+    }
+
+    /// World positions of the solid sample points of this mask
+    fn solid_points(&self) -> impl Iterator<Item = na::Vector2<f32>> + '_ {
+        let BoundingBox { position, size } = self.bounds;
+        let center = position + size * 0.5;
+        let rotation = na::Rotation2::new(self.rotation.to_radians());
+        let (columns, rows) = (self.mask.width * SUBSAMPLES, self.mask.height * SUBSAMPLES);
+
+        (0..rows).flat_map(move |row| {
+            (0..columns).filter_map(move |column| {
+                let uv = glm::vec2(
+                    (column as f32 + 0.5) / columns as f32,
+                    (row as f32 + 0.5) / rows as f32,
+                );
+                let texture_uv = if self.flip_vertical {
+                    glm::vec2(uv.x, 1.0 - uv.y)
+                } else {
+                    uv
+                };
+                if !self.mask.sample(texture_uv) {
+                    return None;
+                }
+
+                let local = uv.component_mul(&size) - size * 0.5;
+                Some(center + rotation * local)
+            })
+        })
+    }
+
+    /// Whether a solid part of this mask covers a solid part of `other`
+    ///
+    /// `other` must not be rotated.
+    pub fn overlaps(&self, other: &PlacedMask) -> bool {
+        if !self.broad_phase().intersect(&other.bounds) {
+            return false;
+        }
+
+        let BoundingBox { position, size } = other.bounds;
+        self.solid_points().any(|point| {
+            let mut uv = (point - position).component_div(&size);
+            if other.flip_vertical {
+                uv.y = 1.0 - uv.y;
+            }
+            other.mask.sample(uv)
+        })
+    }
+
+    /// Whether a solid part of this mask lies inside `other`
+    pub fn overlaps_box(&self, other: &BoundingBox) -> bool {
+        if !self.broad_phase().intersect(other) {
+            return false;
+        }
+
+        let BoundingBox { position, size } = *other;
+        self.solid_points().any(|point| {
+            (position.x..=position.x + size.x).contains(&point.x)
+                && (position.y..=position.y + size.y).contains(&point.y)
+        })
+    }
+}
+
+/// Masks of every sprite that takes part in collisions
+pub struct SpriteMasks {
+    /// Indexed by [`BirdColor`](crate::world::bird::BirdColor) then [`Flap`](crate::world::bird::Flap)
+    pub bird: [[AlphaMask; 3]; 3],
+    pub pipe: AlphaMask,
+}
+
+impl SpriteMasks {
+    pub fn new() -> Self {
+        let bird = [
+            [
+                AlphaMask::from_png(include_bytes!("../../assets/sprites/bluebird-downflap.png")),
+                AlphaMask::from_png(include_bytes!("../../assets/sprites/bluebird-midflap.png")),
+                AlphaMask::from_png(include_bytes!("../../assets/sprites/bluebird-upflap.png")),
+            ],
+            [
+                AlphaMask::from_png(include_bytes!("../../assets/sprites/redbird-downflap.png")),
+                AlphaMask::from_png(include_bytes!("../../assets/sprites/redbird-midflap.png")),
+                AlphaMask::from_png(include_bytes!("../../assets/sprites/redbird-upflap.png")),
+            ],
+            [
+                AlphaMask::from_png(include_bytes!(
+                    "../../assets/sprites/yellowbird-downflap.png"
+                )),
+                AlphaMask::from_png(include_bytes!(
+                    "../../assets/sprites/yellowbird-midflap.png"
+                )),
+                AlphaMask::from_png(include_bytes!("../../assets/sprites/yellowbird-upflap.png")),
+            ],
+        ];
+        let pipe = AlphaMask::from_png(include_bytes!("../../assets/sprites/pipe-green.png"));
+
+        Self { bird, pipe }
+    }
+}
+
+impl Default for SpriteMasks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,341 @@
+//! Game simulation, independent of any windowing or rendering
+//!
+//! Everything in here works in pixels of a fixed size world, usually [`WORLD_SIZE`], and
+//! never touches the GPU, so a [`GameWorld`] can be stepped without a display attached
+//! and plays the same no matter how big the window is.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::gamestate::{GameState, Hittable, PlayState, Update};
+
+pub mod background;
+pub mod bird;
+pub mod difficulty;
+pub mod ground;
+pub mod mask;
+pub mod pipes;
+
+use background::Background;
+use bird::{Bird, BirdColor};
+use difficulty::Difficulty;
+use ground::Ground;
+use mask::{PlacedMask, SpriteMasks};
+use pipes::Pipes;
+
+/// Logical size of the world, the renderer scales it to fit the window
+pub const WORLD_SIZE: (u32, u32) = (700, 970);
+
+/// Simulation runs at 120 Hz
+pub const SIMULATION_STEP: Duration = Duration::from_nanos(1_000_000_000 / 120);
+
+/// Things that happened during a simulation step that a front end may want to react to
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum WorldEvent {
+    Flapped,
+    Scored,
+    /// The bird hit a pipe or the ground and starts falling
+    Hit,
+    /// The bird came to rest on the ground, the run is over
+    GameOver,
+}
+
+/// How the bird is tested against pipes and ground
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CollisionMode {
+    /// Compare the axis-aligned boxes of the sprites
+    #[default]
+    BoundingBox,
+    /// Compare the colliders of the sprites, which for the bird is rotated like its sprite
+    OrientedBox,
+    /// Compare the visible pixels of the sprites, accounting for the bird's rotation
+    Pixel,
+}
+
+pub struct GameWorld {
+    pub config: Config,
+    pub state: GameState,
+    pub background: Background,
+    pub pipes: Pipes,
+    pub ground: Ground,
+    pub bird: Bird,
+    pub hit_detection: bool,
+    pub collision_mode: CollisionMode,
+    masks: SpriteMasks,
+    events: Vec<WorldEvent>,
+    /// Ticks at which the bird flapped during the current run
+    flap_ticks: Vec<u64>,
+    /// State and time in it to go back to when resuming from a pause
+    paused: Option<(PlayState, Duration)>,
+    /// Config the current run started with, see [`GameWorld::run_config`]
+    run_config: Config,
+    run_bird_color: BirdColor,
+}
+
+impl GameWorld {
+    pub fn new(viewport_size: (u32, u32), seed: u64, config: Config) -> Self {
+        let state = GameState {
+            viewport_size,
+            seed,
+            difficulty: Difficulty::at(&config.difficulty, &config.pipes, 0),
+            ..GameState::default()
+        };
+
+        Self {
+            background: Background::new(config.scrolling.background_speed),
+            pipes: Pipes::new(&state, config.pipes),
+            ground: Ground::new(config.scrolling.ground_speed),
+            bird: Bird::new(&state, config.bird),
+            hit_detection: true,
+            collision_mode: CollisionMode::default(),
+            masks: SpriteMasks::new(),
+            events: Vec::new(),
+            flap_ticks: Vec::new(),
+            paused: None,
+            run_config: config.clone(),
+            run_bird_color: BirdColor::Blue,
+            state,
+            config,
+        }
+    }
+
+    /// Switch to `config` and go back to the main menu, keeping the bird color and background
+    pub fn set_config(&mut self, config: Config) {
+        let color = self.bird.color;
+        let texture_variant = self.background.texture_variant;
+
+        self.bird = Bird::new(&self.state, config.bird);
+        self.bird.color = color;
+        self.background = Background::new(config.scrolling.background_speed);
+        self.background.texture_variant = texture_variant;
+        self.ground = Ground::new(config.scrolling.ground_speed);
+        self.pipes.config = config.pipes;
+        self.config = config;
+        self.reset();
+    }
+
+    /// Go back to the main menu
+    pub fn reset(&mut self) {
+        self.set_play_state(PlayState::MainMenu);
+        self.restart();
+    }
+
+    /// Get ready for a new run whose pipes are generated from `seed`, the first flap starts it
+    pub fn start(&mut self, seed: u64) {
+        self.state.seed = seed;
+        self.restart();
+        self.set_play_state(PlayState::Ready);
+    }
+
+    fn set_play_state(&mut self, state: PlayState) {
+        self.state.state = state;
+        self.state.time_in_state = Duration::ZERO;
+        self.paused = None;
+    }
+
+    /// Freeze the world if a run is in progress
+    pub fn pause(&mut self) {
+        if matches!(
+            self.state.state,
+            PlayState::Ready | PlayState::Playing | PlayState::Dying
+        ) {
+            self.paused = Some((self.state.state, self.state.time_in_state));
+            self.state.state = PlayState::Paused;
+            self.state.fly_up = false;
+        }
+    }
+
+    /// Continue exactly where [`GameWorld::pause`] left off
+    pub fn resume(&mut self) {
+        if let Some((state, time_in_state)) = self.paused.take() {
+            self.state.state = state;
+            self.state.time_in_state = time_in_state;
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        if matches!(self.state.state, PlayState::Paused) {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    /// The current state, or the one that was paused
+    pub fn unpaused_state(&self) -> PlayState {
+        self.paused.map_or(self.state.state, |(state, _)| state)
+    }
+
+    fn restart(&mut self) {
+        self.state.score = 0;
+        self.update_difficulty();
+        self.bird.reset(&self.state);
+        self.pipes.reset(&self.state);
+        self.state.tick = 0;
+        self.state.time_since_hit = None;
+        self.flap_ticks.clear();
+        self.run_config = self.live_config();
+        self.run_bird_color = self.bird.color;
+    }
+
+    /// Make the bird flap on the next step, starting the run if it is getting ready
+    pub fn flap(&mut self) {
+        match self.state.state {
+            PlayState::Ready => {
+                // Every run starts from the same position no matter how long the bird bobbed
+                self.restart();
+                self.set_play_state(PlayState::Playing);
+                self.state.fly_up = true;
+            }
+            PlayState::Playing => self.state.fly_up = true,
+            _ => {}
+        }
+    }
+
+    pub fn step(&mut self, dt: Duration) {
+        if matches!(self.state.state, PlayState::Paused) {
+            return;
+        }
+
+        let score = self.state.score;
+        if self.state.fly_up {
+            self.events.push(WorldEvent::Flapped);
+            self.flap_ticks.push(self.state.tick);
+        }
+
+        self.state.time_in_state += dt;
+        if let Some(time_since_hit) = &mut self.state.time_since_hit {
+            *time_since_hit += dt;
+        }
+        self.update_difficulty();
+        self.background.update(dt, &mut self.state);
+        self.pipes.update(dt, &mut self.state);
+        self.ground.update(dt, &mut self.state);
+        self.bird.update(dt, &mut self.state);
+
+        if self.state.score > score {
+            self.events.push(WorldEvent::Scored);
+        }
+
+        if self.hit_detection && matches!(self.state.state, PlayState::Playing) && self.collides() {
+            self.events.push(WorldEvent::Hit);
+            self.set_play_state(PlayState::Dying);
+            self.state.time_since_hit = Some(Duration::ZERO);
+        }
+
+        if matches!(self.state.state, PlayState::Dying) && self.bird.is_on_ground(&self.state) {
+            self.events.push(WorldEvent::GameOver);
+            self.set_play_state(PlayState::GameOver);
+            self.state.best_score = self.state.best_score.max(self.state.score);
+        }
+
+        self.state.fly_up = false;
+        self.state.tick += 1;
+    }
+
+    fn update_difficulty(&mut self) {
+        self.state.difficulty = Difficulty::at(
+            &self.config.difficulty,
+            &self.pipes.config,
+            self.state.score,
+        );
+    }
+
+    /// Whether the bird currently touches the ground or any pipe
+    pub fn collides(&self) -> bool {
+        match self.collision_mode {
+            CollisionMode::BoundingBox => self.boxes_collide(),
+            CollisionMode::OrientedBox => self.colliders_collide(),
+            CollisionMode::Pixel => self.pixels_collide(),
+        }
+    }
+
+    fn boxes_collide(&self) -> bool {
+        let bird_bb = self.bird.bounding_boxes(&self.state)[0];
+        let ground_bb = self.ground.bounding_boxes(&self.state)[0];
+        let pipe_intersect = self
+            .pipes
+            .bounding_boxes(&self.state)
+            .iter()
+            .any(|bb| bb.intersect(&bird_bb));
+
+        bird_bb.intersect(&ground_bb) || pipe_intersect
+    }
+
+    fn colliders_collide(&self) -> bool {
+        let bird = self.bird.colliders(&self.state);
+        let obstacles = self
+            .ground
+            .colliders(&self.state)
+            .into_iter()
+            .chain(self.pipes.colliders(&self.state));
+
+        obstacles
+            .into_iter()
+            .any(|obstacle| bird.iter().any(|collider| collider.intersect(&obstacle)))
+    }
+
+    fn pixels_collide(&self) -> bool {
+        let bird = PlacedMask {
+            mask: &self.masks.bird[self.bird.color as usize][self.bird.flap() as usize],
+            bounds: self.bird.bounding_boxes(&self.state)[0],
+            rotation: self.bird.rotation,
+            flip_vertical: false,
+        };
+
+        // The ground sprite is opaque, so its box is exact
+        let ground_bb = self.ground.bounding_boxes(&self.state)[0];
+        if bird.overlaps_box(&ground_bb) {
+            return true;
+        }
+
+        self.pipes.pairs().any(|pair| {
+            let [top, bottom] = pair.bounding_boxes_at(pair.offset(), self.state.viewport_size);
+            let pipe = |bounds, flip_vertical| PlacedMask {
+                mask: &self.masks.pipe,
+                bounds,
+                rotation: 0.0,
+                flip_vertical,
+            };
+
+            bird.overlaps(&pipe(top, true)) || bird.overlaps(&pipe(bottom, false))
+        })
+    }
+
+    pub fn flap_ticks(&self) -> &[u64] {
+        &self.flap_ticks
+    }
+
+    /// The config the world currently runs with, including changes made to the bird since
+    pub fn live_config(&self) -> Config {
+        let mut config = self.config.clone();
+        config.bird.gravity = self.bird.gravity;
+        config.bird.upwards_force = self.bird.upwards_force;
+        config.bird.flap_frame_duration = self.bird.flap_frame_duration;
+        config.bird.min_rotation = self.bird.min_rotation;
+        config.bird.max_rotation = self.bird.max_rotation;
+        config.bird.rotation_speed = self.bird.rotation_speed;
+        config
+    }
+
+    /// The live config as it was when the current run started
+    ///
+    /// Changes made to the bird during a run are not included, a replay only reproduces
+    /// the run if there were none.
+    pub fn run_config(&self) -> &Config {
+        &self.run_config
+    }
+
+    /// Color of the bird when the current run started, pixel collisions depend on it
+    pub fn run_bird_color(&self) -> BirdColor {
+        self.run_bird_color
+    }
+
+    /// Events raised since the last call
+    pub fn take_events(&mut self) -> Vec<WorldEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
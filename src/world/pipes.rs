@@ -0,0 +1,174 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use nalgebra_glm as glm;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::config::PipesConfig;
+use crate::gamestate::{BoundingBox, GameState, Hittable, PlayState, Update};
+use crate::util::lerp;
+use crate::world::difficulty::Difficulty;
+use crate::world::ground::GROUND_HEIGHT_PERCENT;
+
+/// Minimum amount of pipe visible above and below the aperture
+pub const PIPE_MIN_LENGTH_PERCENT: f32 = 0.05;
+/// Largest aperture that still leaves the minimum length of both pipes above the ground
+pub const MAX_APERTURE_PERCENT: f32 = 1.0 - GROUND_HEIGHT_PERCENT - 2.0 * PIPE_MIN_LENGTH_PERCENT;
+
+#[derive(Debug, Copy, Clone)]
+pub struct PipePair {
+    offset: f32,
+    previous_offset: f32,
+    /// Vertical center of the aperture as a fraction of the viewport height
+    gap_center: f32,
+    aperture_percent: f32,
+    width_percent: f32,
+    passed: bool,
+}
+
+impl PipePair {
+    /// Left edge of the pair
+    pub fn offset(&self) -> f32 {
+        self.offset
+    }
+
+    pub fn interpolated_offset(&self, t: f32) -> f32 {
+        lerp(self.previous_offset, self.offset, t)
+    }
+
+    /// Top and bottom pipe of this pair if its left edge were at `offset`
+    pub fn bounding_boxes_at(&self, offset: f32, viewport_size: (u32, u32)) -> [BoundingBox; 2] {
+        let (width, height) = (viewport_size.0 as f32, viewport_size.1 as f32);
+        let top_height = self.gap_center - self.aperture_percent / 2.0;
+        let bottom_position = self.gap_center + self.aperture_percent / 2.0;
+
+        [
+            BoundingBox {
+                position: glm::vec2(offset, 0.0),
+                size: glm::vec2(width * self.width_percent, height * top_height),
+            },
+            BoundingBox {
+                position: glm::vec2(offset, height * bottom_position),
+                size: glm::vec2(width * self.width_percent, height * (1.0 - bottom_position)),
+            },
+        ]
+    }
+}
+
+pub struct Pipes {
+    pairs: VecDeque<PipePair>,
+    rng: ChaCha8Rng,
+    /// Space after the last pair before the next one as a fraction of the viewport width
+    next_gap_percent: f32,
+    pub config: PipesConfig,
+}
+
+impl Pipes {
+    pub fn new(game_state: &GameState, config: PipesConfig) -> Self {
+        let mut pipes = Self {
+            pairs: VecDeque::new(),
+            rng: ChaCha8Rng::seed_from_u64(game_state.seed),
+            next_gap_percent: config.gap_percent,
+            config,
+        };
+        pipes.spawn_initial_pairs(game_state);
+        pipes
+    }
+
+    /// Restart the pipe sequence from `game_state.seed`
+    pub fn reset(&mut self, game_state: &GameState) {
+        self.rng = ChaCha8Rng::seed_from_u64(game_state.seed);
+        self.pairs.clear();
+        self.spawn_initial_pairs(game_state);
+    }
+
+    pub fn pairs(&self) -> impl Iterator<Item = &PipePair> {
+        self.pairs.iter()
+    }
+
+    fn spawn_initial_pairs(&mut self, game_state: &GameState) {
+        let width = game_state.viewport_size.0 as f32;
+        self.spawn_pair(width * 0.50, &game_state.difficulty);
+        self.fill_pairs(width, &game_state.difficulty);
+    }
+
+    fn spawn_pair(&mut self, offset: f32, difficulty: &Difficulty) {
+        // Keep the whole aperture, plus a bit of pipe, above the ground
+        let aperture_percent = difficulty.aperture_percent;
+        let min_center = PIPE_MIN_LENGTH_PERCENT + aperture_percent / 2.0;
+        let max_center =
+            1.0 - GROUND_HEIGHT_PERCENT - PIPE_MIN_LENGTH_PERCENT - aperture_percent / 2.0;
+        let gap_center = self.rng.gen_range(min_center..=max_center);
+
+        self.pairs.push_back(PipePair {
+            offset,
+            previous_offset: offset,
+            gap_center,
+            aperture_percent,
+            width_percent: self.config.width_percent,
+            passed: false,
+        });
+
+        // Only draw from the rng when needed so pipe sequences don't change until spacing varies
+        let variation = difficulty.spacing_variation;
+        self.next_gap_percent = if variation > 0.0 {
+            self.config.gap_percent * (1.0 + self.rng.gen_range(-variation..=variation))
+        } else {
+            self.config.gap_percent
+        };
+    }
+
+    /// Append pipe pairs to the right until the viewport is covered
+    fn fill_pairs(&mut self, width: f32, difficulty: &Difficulty) {
+        while let Some(last) = self.pairs.back() {
+            let offset = last.offset + width * last.width_percent + width * self.next_gap_percent;
+            if offset >= width {
+                break;
+            }
+            self.spawn_pair(offset, difficulty);
+        }
+    }
+}
+
+impl Update for Pipes {
+    fn update(&mut self, dt: Duration, game_state: &mut GameState) {
+        for pair in self.pairs.iter_mut() {
+            pair.previous_offset = pair.offset;
+        }
+
+        if matches!(game_state.state, PlayState::Playing) {
+            let width = game_state.viewport_size.0 as f32;
+            let bird_left = game_state.bird_bounding_box.position.x;
+
+            let speed = self.config.speed * game_state.difficulty.speed_multiplier;
+            for pair in self.pairs.iter_mut() {
+                pair.offset -= (dt.as_secs_f32() * speed) * width;
+
+                // A pair counts once the bird's left edge is past the pipe's right edge
+                if !pair.passed && pair.offset + width * pair.width_percent < bird_left {
+                    pair.passed = true;
+                    game_state.score += 1;
+                }
+            }
+
+            while self
+                .pairs
+                .front()
+                .is_some_and(|pair| pair.offset < -(width * pair.width_percent))
+            {
+                self.pairs.pop_front();
+            }
+            self.fill_pairs(width, &game_state.difficulty);
+        }
+    }
+}
+
+impl Hittable for Pipes {
+    fn bounding_boxes(&self, game_state: &GameState) -> Vec<BoundingBox> {
+        self.pairs
+            .iter()
+            .flat_map(|pair| pair.bounding_boxes_at(pair.offset, game_state.viewport_size))
+            .collect()
+    }
+}
@@ -0,0 +1,60 @@
+use flappybirb::atlas::AtlasBuilder;
+use flappybirb::renderer::{RenderOptions, SpriteBatch};
+use flappybirb::texture::Texture;
+use image::{Rgba, RgbaImage};
+use nalgebra_glm as glm;
+
+#[test]
+fn packs_sprites_without_overlap() {
+    let mut atlas = AtlasBuilder::new();
+    let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+    let textures: Vec<Texture> = [(600, 20), (600, 40), (10, 10)]
+        .into_iter()
+        .zip(colors)
+        .map(|((width, height), color)| {
+            atlas.add(RgbaImage::from_pixel(width, height, Rgba(color)))
+        })
+        .collect();
+    let packed = atlas.pack();
+    let (width, height) = packed.image.dimensions();
+
+    for (texture, color) in textures.iter().zip(colors) {
+        let region = packed.region(texture);
+        let left = (region.uv_min[0] * width as f32).round() as u32;
+        let top = (region.uv_min[1] * height as f32).round() as u32;
+        let right = (region.uv_max[0] * width as f32).round() as u32;
+        let bottom = (region.uv_max[1] * height as f32).round() as u32;
+        assert_eq!((right - left, bottom - top), texture.size);
+
+        // Every pixel of the region belongs to the sprite, including the padding around it
+        for y in top.saturating_sub(1)..(bottom + 1).min(height) {
+            for x in left.saturating_sub(1)..(right + 1).min(width) {
+                assert_eq!(*packed.image.get_pixel(x, y), Rgba(color));
+            }
+        }
+    }
+}
+
+#[test]
+fn splits_scrolled_sprites_where_they_wrap() {
+    let mut atlas = AtlasBuilder::new();
+    let texture = Texture::from_rgba(vec![255; 4], (1, 1), &mut atlas);
+    let mut batch = SpriteBatch::new();
+
+    batch.push(
+        &texture,
+        RenderOptions {
+            size: glm::vec2(100.0, 10.0),
+            pan: 1.25,
+            ..RenderOptions::default()
+        },
+    );
+
+    let [first, second] = batch.quads() else {
+        panic!("expected two quads, got {}", batch.len());
+    };
+    assert_eq!((first.position.x, first.size.x), (0.0, 75.0));
+    assert_eq!((first.uv_min[0], first.uv_max[0]), (0.25, 1.0));
+    assert_eq!((second.position.x, second.size.x), (75.0, 25.0));
+    assert_eq!((second.uv_min[0], second.uv_max[0]), (0.0, 0.25));
+}
@@ -0,0 +1,40 @@
+use flappybirb::config::Config;
+use flappybirb::{GameWorld, SIMULATION_STEP, WORLD_SIZE};
+
+#[test]
+fn pitch_follows_velocity_and_wings_freeze_in_a_nosedive() {
+    let mut world = GameWorld::new(WORLD_SIZE, 3, Config::default());
+    world.hit_detection = false;
+    world.start(3);
+    world.flap();
+    world.step(SIMULATION_STEP);
+    let (min_rotation, max_rotation) = (world.bird.min_rotation, world.bird.max_rotation);
+    assert_eq!(world.bird.rotation, min_rotation);
+
+    // Nose up for as long as the bird rises
+    let mut frames = Vec::new();
+    while world.bird.y_velocity < 0.0 {
+        assert_eq!(world.bird.rotation, min_rotation);
+        world.step(SIMULATION_STEP);
+        frames.push(world.bird.flap() as usize);
+    }
+    frames.dedup();
+    assert!(frames.len() > 1, "wings should flap on the way up");
+
+    // Then pitch down steadily until it is nosediving
+    let mut rotation = world.bird.rotation;
+    while !world.bird.is_nosediving() {
+        world.step(SIMULATION_STEP);
+        assert!(world.bird.rotation >= rotation);
+        assert!(world.bird.rotation <= max_rotation);
+        rotation = world.bird.rotation;
+    }
+    assert_eq!(world.bird.rotation, max_rotation);
+
+    let frame = world.bird.flap() as usize;
+    for _ in 0..120 {
+        world.step(SIMULATION_STEP);
+        assert_eq!(world.bird.rotation, max_rotation);
+        assert_eq!(world.bird.flap() as usize, frame);
+    }
+}
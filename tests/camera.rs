@@ -0,0 +1,30 @@
+use flappybirb::renderer::Camera;
+use glium::Rect;
+
+fn rect(left: u32, bottom: u32, width: u32, height: u32) -> Rect {
+    Rect {
+        left,
+        bottom,
+        width,
+        height,
+    }
+}
+
+#[test]
+fn letterboxes_to_keep_aspect_ratio() {
+    let camera = Camera::new((100, 200), (400, 200), false);
+    assert_eq!(camera.viewport(), rect(150, 0, 100, 200));
+
+    let camera = Camera::new((100, 200), (50, 400), false);
+    assert_eq!(camera.viewport(), rect(0, 150, 50, 100));
+}
+
+#[test]
+fn integer_scale_only_when_it_fits() {
+    let camera = Camera::new((100, 200), (250, 500), true);
+    assert_eq!(camera.viewport(), rect(25, 50, 200, 400));
+    assert_eq!(camera.scale(), 2.0);
+
+    let camera = Camera::new((100, 200), (50, 100), true);
+    assert_eq!(camera.viewport(), rect(0, 0, 50, 100));
+}
@@ -0,0 +1,86 @@
+use flappybirb::{BoundingBox, Collider, OrientedBoundingBox};
+use nalgebra as na;
+
+fn bounding_box(x: f32, y: f32, width: f32, height: f32) -> BoundingBox {
+    BoundingBox {
+        position: na::Vector2::new(x, y),
+        size: na::Vector2::new(width, height),
+    }
+}
+
+#[test]
+fn unrotated_matches_axis_aligned() {
+    let a = bounding_box(0.0, 0.0, 10.0, 10.0);
+    for b in [
+        bounding_box(5.0, 5.0, 10.0, 10.0),
+        bounding_box(20.0, 0.0, 10.0, 10.0),
+        bounding_box(0.0, 11.0, 10.0, 10.0),
+        bounding_box(-5.0, -5.0, 30.0, 30.0),
+    ] {
+        let oriented = OrientedBoundingBox::from_bounding_box(&a, 0.0);
+        assert_eq!(oriented.intersect_box(&b), a.intersect(&b), "{b:?}");
+    }
+}
+
+#[test]
+fn touching_edges_intersect() {
+    let a = OrientedBoundingBox::from_bounding_box(&bounding_box(0.0, 0.0, 10.0, 10.0), 0.0);
+    assert!(a.intersect_box(&bounding_box(10.0, 0.0, 10.0, 10.0)));
+    assert!(!a.intersect_box(&bounding_box(10.1, 0.0, 10.0, 10.0)));
+}
+
+#[test]
+fn rotated_corner_misses_box_its_bounds_hit() {
+    // A square rotated by 45° is a diamond, its bounds overlap a box near the corner
+    // of those bounds but the diamond itself does not
+    let diamond = OrientedBoundingBox::from_bounding_box(&bounding_box(0.0, 0.0, 10.0, 10.0), 45.0);
+    let bounds = diamond.bounds();
+    let near_corner = bounding_box(bounds.position.x - 1.0, bounds.position.y - 1.0, 2.0, 2.0);
+
+    assert!(bounds.intersect(&near_corner));
+    assert!(!diamond.intersect_box(&near_corner));
+}
+
+#[test]
+fn rotated_point_reaches_box_outside_unrotated_extent() {
+    // Rotating a wide box makes it reach above its unrotated top edge
+    let wide = bounding_box(0.0, 10.0, 40.0, 4.0);
+    let above = bounding_box(30.0, 0.0, 10.0, 8.0);
+
+    assert!(!wide.intersect(&above));
+    assert!(OrientedBoundingBox::from_bounding_box(&wide, -20.0).intersect_box(&above));
+    assert!(!OrientedBoundingBox::from_bounding_box(&wide, 20.0).intersect_box(&above));
+}
+
+#[test]
+fn quarter_turn_swaps_extents() {
+    let tall = OrientedBoundingBox::from_bounding_box(&bounding_box(0.0, 0.0, 2.0, 20.0), 90.0);
+    let bounds = tall.bounds();
+
+    assert!((bounds.size.x - 20.0).abs() < 1e-4);
+    assert!((bounds.size.y - 2.0).abs() < 1e-4);
+    assert!(tall.intersect_box(&bounding_box(9.5, 9.0, 1.0, 1.0)));
+    assert!(!tall.intersect_box(&bounding_box(0.0, 0.0, 1.0, 1.0)));
+}
+
+#[test]
+fn oriented_boxes_intersect_each_other() {
+    let a = OrientedBoundingBox::from_bounding_box(&bounding_box(0.0, 0.0, 10.0, 10.0), 45.0);
+    let b = OrientedBoundingBox::from_bounding_box(&bounding_box(13.0, 0.0, 10.0, 10.0), 45.0);
+    let c = OrientedBoundingBox::from_bounding_box(&bounding_box(15.0, 0.0, 10.0, 10.0), 45.0);
+
+    // Diamonds reach 5√2 ≈ 7.07 from their centers, 13 apart they overlap, 15 apart they don't
+    assert!(a.intersect(&b));
+    assert!(!a.intersect(&c));
+}
+
+#[test]
+fn collider_dispatches_on_shape() {
+    let wide = bounding_box(0.0, 10.0, 40.0, 4.0);
+    let above = Collider::Box(bounding_box(30.0, 0.0, 10.0, 8.0));
+
+    assert!(!Collider::Box(wide).intersect(&above));
+    let rotated = Collider::Oriented(OrientedBoundingBox::from_bounding_box(&wide, -20.0));
+    assert!(rotated.intersect(&above));
+    assert!(above.intersect(&rotated));
+}
@@ -0,0 +1,19 @@
+use flappybirb::config::{Config, Preset};
+
+#[test]
+fn file_without_preset_keeps_its_values() {
+    let config = Config::from_toml("[bird]\ngravity = 700.0\n").unwrap();
+    assert_eq!(config.preset, Preset::Custom);
+    assert_eq!(config.preset.apply(&config).bird.gravity, 700.0);
+
+    // Without any file the game plays the normal preset
+    assert_eq!(Config::default().preset, Preset::Normal);
+}
+
+#[test]
+fn named_preset_overrides_the_file() {
+    let config = Config::from_toml("preset = \"hard\"\n\n[bird]\ngravity = 400.0\n").unwrap();
+    assert_eq!(config.preset, Preset::Hard);
+    assert_eq!(config.bird.gravity, 400.0);
+    assert_ne!(config.preset.apply(&config).bird.gravity, 400.0);
+}
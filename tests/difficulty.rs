@@ -0,0 +1,71 @@
+use flappybirb::config::{Config, DifficultyConfig, PipesConfig};
+use flappybirb::world::difficulty::{Curve, Difficulty};
+use flappybirb::world::pipes::MAX_APERTURE_PERCENT;
+use flappybirb::{GameWorld, SIMULATION_STEP, WORLD_SIZE};
+
+#[test]
+fn curve_interpolates_between_points() {
+    let curve = Curve::new(&[(10, 1.0), (20, 2.0)]);
+    assert_eq!(curve.at(0), 1.0);
+    assert_eq!(curve.at(10), 1.0);
+    assert_eq!(curve.at(15), 1.5);
+    assert_eq!(curve.at(20), 2.0);
+    assert_eq!(curve.at(100), 2.0);
+}
+
+#[test]
+fn difficulty_is_capped() {
+    let config = DifficultyConfig {
+        speed: Curve::new(&[(0, 1.0), (10, 3.0)]),
+        aperture: Curve::new(&[(0, 1.0), (10, 0.1)]),
+        spacing_variation: Curve::new(&[(0, 0.0), (10, 0.9)]),
+        max_speed_multiplier: 2.0,
+        min_aperture_percent: 0.12,
+        max_spacing_variation: 0.25,
+    };
+    let pipes = PipesConfig::default();
+
+    let start = Difficulty::at(&config, &pipes, 0);
+    assert_eq!(start.speed_multiplier, 1.0);
+    assert_eq!(start.aperture_percent, pipes.aperture_percent);
+    assert_eq!(start.spacing_variation, 0.0);
+
+    let end = Difficulty::at(&config, &pipes, 50);
+    assert_eq!(end.speed_multiplier, 2.0);
+    assert_eq!(end.aperture_percent, 0.12);
+    assert_eq!(end.spacing_variation, 0.25);
+}
+
+#[test]
+fn unsorted_curve_is_rejected() {
+    let config = Config::from_toml(
+        "[difficulty]\nspeed = [{ score = 10, value = 1.0 }, { score = 5, value = 1.2 }]\n",
+    )
+    .unwrap();
+    assert!(config.validate().is_err());
+    assert!(Config::default().validate().is_ok());
+}
+
+#[test]
+fn aperture_never_leaves_the_viewport() {
+    let mut config = Config::default();
+    config.difficulty.aperture = Curve::new(&[(0, 1.0), (5, 10.0)]);
+    assert!(config.validate().is_err());
+
+    let wide = Difficulty::at(&config.difficulty, &config.pipes, 5);
+    assert_eq!(wide.aperture_percent, MAX_APERTURE_PERCENT);
+
+    // Spawning pipes with the widest aperture still fits them in
+    let mut world = GameWorld::new(WORLD_SIZE, 1, config);
+    world.hit_detection = false;
+    world.start(1);
+    world.flap();
+    world.state.score = 5;
+    for _ in 0..2_000 {
+        world.step(SIMULATION_STEP);
+    }
+    assert_eq!(
+        world.state.difficulty.aperture_percent,
+        MAX_APERTURE_PERCENT
+    );
+}
@@ -0,0 +1,57 @@
+use flappybirb::config::InputConfig;
+use flappybirb::gamepad::{GamepadEvent, Gamepads};
+use flappybirb::input::{Action, Binding, GamepadButton, InputMap};
+use winit::event::ElementState;
+
+fn connect(gamepads: &mut Gamepads, id: usize, name: &str) {
+    gamepads.inject(GamepadEvent::Connected {
+        id,
+        name: name.to_string(),
+    });
+}
+
+fn press(gamepads: &mut Gamepads, id: usize, button: GamepadButton) {
+    for state in [ElementState::Pressed, ElementState::Released] {
+        gamepads.inject(GamepadEvent::Button { id, button, state });
+    }
+}
+
+#[test]
+fn injected_buttons_map_to_actions() {
+    let mut gamepads = Gamepads::without_backend();
+    let mut input = InputMap::new(InputConfig::default());
+    connect(&mut gamepads, 0, "Pad");
+    press(&mut gamepads, 0, GamepadButton::South);
+    press(&mut gamepads, 0, GamepadButton::Start);
+
+    for (button, state) in gamepads.poll() {
+        input.handle(Binding::Gamepad(button), state);
+    }
+    for action in [Action::Flap, Action::Start, Action::Pause] {
+        assert!(input.pressed(action), "{action:?}");
+        assert!(input.released(action), "{action:?}");
+        assert!(!input.held(action), "{action:?}");
+    }
+    assert!(!input.pressed(Action::Restart));
+    assert!(gamepads.poll().is_empty());
+}
+
+#[test]
+fn last_used_gamepad_is_active() {
+    let mut gamepads = Gamepads::without_backend();
+    assert_eq!(gamepads.active(), None);
+
+    connect(&mut gamepads, 0, "First");
+    connect(&mut gamepads, 1, "Second");
+    gamepads.poll();
+    assert_eq!(gamepads.active(), Some("First"));
+
+    press(&mut gamepads, 1, GamepadButton::South);
+    gamepads.poll();
+    assert_eq!(gamepads.active(), Some("Second"));
+
+    gamepads.inject(GamepadEvent::Disconnected { id: 1 });
+    gamepads.poll();
+    assert_eq!(gamepads.active(), Some("First"));
+    assert_eq!(gamepads.connected().collect::<Vec<_>>(), ["First"]);
+}
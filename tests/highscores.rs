@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+
+use flappybirb::config::Preset;
+use flappybirb::highscores::{HighScoreEntry, HighScores, MAX_ENTRIES};
+use flappybirb::world::background::TextureVariant;
+use flappybirb::world::bird::BirdColor;
+
+/// Fresh file path in a directory of its own under the system temp dir
+fn temp_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("flappybirb-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir.join("highscores.toml")
+}
+
+fn entry(score: u32, seed: u64) -> HighScoreEntry {
+    HighScoreEntry {
+        score,
+        timestamp: seed,
+        bird_color: BirdColor::Blue,
+        background: TextureVariant::Day,
+        seed,
+        preset: Preset::Normal,
+    }
+}
+
+fn seeds(high_scores: &HighScores) -> Vec<u64> {
+    high_scores
+        .entries(Preset::Normal)
+        .map(|entry| entry.seed)
+        .collect()
+}
+
+#[test]
+fn round_trips_through_a_file() {
+    let path = temp_path("round-trip");
+    let mut high_scores = HighScores::load(path.clone());
+    assert_eq!(high_scores.entries(Preset::Normal).count(), 0);
+
+    high_scores.insert(entry(3, 1));
+    high_scores.insert(entry(7, 2));
+    high_scores.save().unwrap();
+
+    let loaded = HighScores::load(path.clone());
+    assert_eq!(
+        loaded.entries(Preset::Normal).cloned().collect::<Vec<_>>(),
+        [entry(7, 2), entry(3, 1)]
+    );
+    assert_eq!(loaded.best(Preset::Normal), 7);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn ties_keep_older_runs_first_and_table_is_truncated() {
+    let path = temp_path("truncate");
+    let mut high_scores = HighScores::load(path.clone());
+
+    assert_eq!(high_scores.insert(entry(5, 1)), Some(0));
+    assert_eq!(high_scores.insert(entry(5, 2)), Some(1));
+    assert_eq!(high_scores.insert(entry(9, 3)), Some(0));
+    assert_eq!(seeds(&high_scores), [3, 1, 2]);
+
+    for seed in 4..20 {
+        high_scores.insert(entry(6, seed));
+    }
+    assert_eq!(high_scores.entries(Preset::Normal).count(), MAX_ENTRIES);
+    assert_eq!(high_scores.insert(entry(1, 99)), None);
+
+    high_scores.save().unwrap();
+    let loaded = HighScores::load(path.clone());
+    assert_eq!(seeds(&loaded), seeds(&high_scores));
+    assert_eq!(seeds(&loaded)[..3], [3, 4, 5]);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn corrupted_file_starts_empty_and_is_overwritten() {
+    let path = temp_path("corrupted");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "this is [not a high score table").unwrap();
+
+    let mut high_scores = HighScores::load(path.clone());
+    assert_eq!(high_scores.entries(Preset::Normal).count(), 0);
+
+    high_scores.insert(entry(4, 1));
+    high_scores.save().unwrap();
+    assert_eq!(seeds(&HighScores::load(path.clone())), [1]);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn unknown_version_starts_empty() {
+    let path = temp_path("version");
+    let mut high_scores = HighScores::load(path.clone());
+    high_scores.insert(entry(4, 1));
+    high_scores.save().unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let version = contents
+        .lines()
+        .find(|line| line.starts_with("version"))
+        .unwrap();
+    std::fs::write(&path, contents.replace(version, "version = 999")).unwrap();
+
+    assert_eq!(
+        HighScores::load(path.clone())
+            .entries(Preset::Normal)
+            .count(),
+        0
+    );
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn tables_from_before_presets_become_normal() {
+    let path = temp_path("presetless");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &path,
+        r#"version = 1
+
+[[entries]]
+score = 12
+timestamp = 1700000000
+bird_color = "red"
+background = "night"
+seed = "42"
+"#,
+    )
+    .unwrap();
+
+    let high_scores = HighScores::load(path.clone());
+    assert_eq!(seeds(&high_scores), [42]);
+    assert_eq!(high_scores.best(Preset::Hard), 0);
+
+    high_scores.save().unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.starts_with("version = 2"), "{contents}");
+    assert!(contents.contains("preset = \"normal\""), "{contents}");
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use flappybirb::config::{Config, InputConfig};
+use flappybirb::input::{Action, Binding, InputMap, MAX_HOLD_TO_FLAP_RATE};
+use winit::event::{ElementState, VirtualKeyCode};
+
+const SPACE: Binding = Binding::Key(VirtualKeyCode::Space);
+
+#[test]
+fn key_repeat_is_one_press() {
+    let mut input = InputMap::new(InputConfig::default());
+    input.handle(SPACE, ElementState::Pressed);
+    assert!(input.pressed(Action::Flap));
+    input.end_frame();
+
+    input.handle(SPACE, ElementState::Pressed);
+    input.handle(SPACE, ElementState::Pressed);
+    assert!(!input.pressed(Action::Flap));
+    assert!(input.held(Action::Flap));
+
+    input.handle(SPACE, ElementState::Released);
+    assert!(input.released(Action::Flap));
+    assert!(!input.held(Action::Flap));
+}
+
+#[test]
+fn release_without_press_does_nothing() {
+    // The window passes on every release, also of presses the debug window swallowed
+    let mut input = InputMap::new(InputConfig::default());
+    input.handle(SPACE, ElementState::Released);
+    assert!(!input.released(Action::Flap));
+
+    input.handle(SPACE, ElementState::Pressed);
+    assert!(input.pressed(Action::Flap));
+}
+
+#[test]
+fn held_action_needs_all_bindings_released() {
+    let mut input = InputMap::new(InputConfig::default());
+    let click = Binding::Mouse(winit::event::MouseButton::Left);
+    input.handle(SPACE, ElementState::Pressed);
+    input.handle(click, ElementState::Pressed);
+    input.handle(SPACE, ElementState::Released);
+    assert!(input.held(Action::Flap));
+    assert!(!input.released(Action::Flap));
+
+    input.handle(click, ElementState::Released);
+    assert!(input.released(Action::Flap));
+}
+
+#[test]
+fn hold_to_flap_repeats_at_rate() {
+    let mut input = InputMap::new(InputConfig {
+        hold_to_flap_rate: 4.0,
+        ..InputConfig::default()
+    });
+    input.handle(SPACE, ElementState::Pressed);
+    input.end_frame();
+
+    let mut flaps = 0;
+    for _ in 0..100 {
+        input.update(Duration::from_millis(10));
+        if input.pressed(Action::Flap) {
+            flaps += 1;
+        }
+        input.end_frame();
+    }
+    assert_eq!(flaps, 4);
+}
+
+#[test]
+fn hold_to_flap_rate_is_validated() {
+    let mut config = Config::default();
+    for rate in [0.0, 1e-39, MAX_HOLD_TO_FLAP_RATE] {
+        config.input.hold_to_flap_rate = rate;
+        assert!(config.validate().is_ok(), "{rate}");
+    }
+    for rate in [-1.0, MAX_HOLD_TO_FLAP_RATE + 1.0, f32::NAN] {
+        config.input.hold_to_flap_rate = rate;
+        assert!(config.validate().is_err(), "{rate}");
+    }
+}
+
+#[test]
+fn tiny_hold_to_flap_rate_never_repeats() {
+    // The period of this rate overflows a `Duration`
+    let mut input = InputMap::new(InputConfig {
+        hold_to_flap_rate: 1e-39,
+        ..InputConfig::default()
+    });
+    input.handle(SPACE, ElementState::Pressed);
+    input.end_frame();
+
+    input.update(Duration::from_secs(60));
+    assert!(!input.pressed(Action::Flap));
+}
+
+#[test]
+fn captured_input_is_bound_and_swallowed() {
+    let mut input = InputMap::new(InputConfig::default());
+    let up = Binding::Key(VirtualKeyCode::Up);
+    input.capture(Action::Flap);
+    input.handle(up, ElementState::Pressed);
+    input.handle(up, ElementState::Released);
+    assert!(!input.pressed(Action::Flap));
+    assert!(!input.released(Action::Flap));
+    assert!(input.bindings(Action::Flap).contains(&up));
+
+    input.handle(up, ElementState::Pressed);
+    assert!(input.pressed(Action::Flap));
+}
+
+#[test]
+fn alt_key_is_released_without_alt() {
+    let mut input = InputMap::new(InputConfig::default());
+    input.handle(
+        Binding::AltKey(VirtualKeyCode::Return),
+        ElementState::Pressed,
+    );
+    assert!(input.pressed(Action::ToggleFullscreen));
+
+    input.handle(Binding::Key(VirtualKeyCode::Return), ElementState::Released);
+    assert!(input.released(Action::ToggleFullscreen));
+    assert!(!input.held(Action::ToggleFullscreen));
+}
+
+#[test]
+fn alt_only_matters_for_alt_bindings() {
+    let mut input = InputMap::new(InputConfig::default());
+    assert_eq!(input.key_binding(VirtualKeyCode::Space, true), SPACE);
+    assert_eq!(
+        input.key_binding(VirtualKeyCode::Return, true),
+        Binding::AltKey(VirtualKeyCode::Return)
+    );
+    assert_eq!(
+        input.key_binding(VirtualKeyCode::Return, false),
+        Binding::Key(VirtualKeyCode::Return)
+    );
+
+    input.handle(
+        input.key_binding(VirtualKeyCode::P, true),
+        ElementState::Pressed,
+    );
+    assert!(input.pressed(Action::Pause));
+
+    input.capture(Action::Quit);
+    let binding = input.key_binding(VirtualKeyCode::F4, true);
+    input.handle(binding, ElementState::Pressed);
+    assert_eq!(
+        input.bindings(Action::Quit),
+        [Binding::AltKey(VirtualKeyCode::F4)]
+    );
+}
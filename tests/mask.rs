@@ -0,0 +1,98 @@
+use flappybirb::world::mask::{AlphaMask, PlacedMask};
+use flappybirb::BoundingBox;
+use image::{Rgba, RgbaImage};
+use nalgebra as na;
+
+fn bounding_box(x: f32, y: f32, width: f32, height: f32) -> BoundingBox {
+    BoundingBox {
+        position: na::Vector2::new(x, y),
+        size: na::Vector2::new(width, height),
+    }
+}
+
+/// 4x4 mask that is solid where `solid(x, y)` holds
+fn mask(solid: impl Fn(u32, u32) -> bool) -> AlphaMask {
+    AlphaMask::from_rgba(&RgbaImage::from_fn(4, 4, |x, y| {
+        Rgba([255, 255, 255, if solid(x, y) { 255 } else { 0 }])
+    }))
+}
+
+fn placed(mask: &AlphaMask, bounds: BoundingBox, rotation: f32) -> PlacedMask<'_> {
+    PlacedMask {
+        mask,
+        bounds,
+        rotation,
+        flip_vertical: false,
+    }
+}
+
+#[test]
+fn alpha_above_half_is_solid() {
+    let image = RgbaImage::from_fn(3, 1, |x, _| Rgba([0, 0, 0, [127, 128, 255][x as usize]]));
+    let mask = AlphaMask::from_rgba(&image);
+    assert!(!mask.is_solid(0, 0));
+    assert!(mask.is_solid(1, 0));
+    assert!(mask.is_solid(2, 0));
+    assert!(!mask.is_solid(3, 0));
+}
+
+#[test]
+fn only_solid_parts_overlap() {
+    let top_left = mask(|x, y| x < 2 && y < 2);
+    let full = mask(|_, _| true);
+    let a = placed(&top_left, bounding_box(0.0, 0.0, 20.0, 20.0), 0.0);
+
+    // Inside the bounds of `a` but only over its transparent part
+    let b = placed(&full, bounding_box(12.0, 12.0, 10.0, 10.0), 0.0);
+    assert!(a.bounds.intersect(&b.bounds));
+    assert!(!a.overlaps(&b));
+    assert!(!a.overlaps_box(&b.bounds));
+
+    let c = placed(&full, bounding_box(5.0, 5.0, 10.0, 10.0), 0.0);
+    assert!(a.overlaps(&c));
+    assert!(a.overlaps_box(&c.bounds));
+
+    // Flipped vertically the solid part is at the bottom left instead
+    let flipped = PlacedMask {
+        flip_vertical: true,
+        ..placed(&top_left, bounding_box(5.0, 5.0, 20.0, 20.0), 0.0)
+    };
+    let left_edge = placed(&full, bounding_box(0.0, 0.0, 6.0, 10.0), 0.0);
+    assert!(!left_edge.overlaps(&flipped));
+    let lower_left = placed(&full, bounding_box(0.0, 20.0, 6.0, 10.0), 0.0);
+    assert!(lower_left.overlaps(&flipped));
+}
+
+#[test]
+fn rotation_moves_solid_parts() {
+    let right_half = mask(|x, _| x >= 2);
+    let bounds = bounding_box(0.0, 0.0, 20.0, 20.0);
+    let right = bounding_box(16.0, 2.0, 4.0, 4.0);
+    let bottom = bounding_box(2.0, 16.0, 4.0, 4.0);
+
+    let unrotated = placed(&right_half, bounds, 0.0);
+    assert!(unrotated.overlaps_box(&right));
+    assert!(!unrotated.overlaps_box(&bottom));
+
+    // Clockwise by a quarter turn the right half ends up at the bottom
+    let rotated = placed(&right_half, bounds, 90.0);
+    assert!(!rotated.overlaps_box(&right));
+    assert!(rotated.overlaps_box(&bottom));
+}
+
+#[test]
+fn broad_phase_covers_the_rotated_mask() {
+    let full = mask(|_, _| true);
+    let diamond = placed(&full, bounding_box(0.0, 0.0, 10.0, 10.0), 45.0);
+    let broad = diamond.broad_phase();
+
+    let half_diagonal = 50.0_f32.sqrt();
+    assert!((broad.position.x - (5.0 - half_diagonal)).abs() < 1e-4);
+    assert!((broad.size.x - 2.0 * half_diagonal).abs() < 1e-4);
+
+    // Outside the broad phase nothing is tested, inside its corners the diamond misses
+    assert!(!diamond.overlaps_box(&bounding_box(20.0, 0.0, 5.0, 5.0)));
+    let corner = bounding_box(broad.position.x, broad.position.y, 1.0, 1.0);
+    assert!(broad.intersect(&corner));
+    assert!(!diamond.overlaps_box(&corner));
+}
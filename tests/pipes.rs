@@ -0,0 +1,44 @@
+use flappybirb::config::Config;
+use flappybirb::{GameWorld, SIMULATION_STEP, WORLD_SIZE};
+
+/// Heights of the top pipes of the first `count` pairs a run with `seed` spawns
+fn gap_sequence(seed: u64, count: usize) -> Vec<f32> {
+    let mut world = GameWorld::new(WORLD_SIZE, seed, Config::default());
+    world.hit_detection = false;
+    world.start(seed);
+
+    let mut heights: Vec<f32> = Vec::new();
+    for tick in 0.. {
+        let on_screen: Vec<f32> = world
+            .pipes
+            .pairs()
+            .map(|pair| pair.bounding_boxes_at(pair.offset(), WORLD_SIZE)[0].size.y)
+            .collect();
+        // Pairs spawn on the right, the last one recorded is still on screen
+        let new = heights
+            .last()
+            .and_then(|last| on_screen.iter().position(|height| height == last))
+            .map_or(0, |position| position + 1);
+        heights.extend(&on_screen[new..]);
+        if heights.len() >= count {
+            break;
+        }
+
+        if tick % 20 == 0 {
+            world.flap();
+        }
+        world.step(SIMULATION_STEP);
+    }
+    heights.truncate(count);
+    heights
+}
+
+#[test]
+fn same_seed_spawns_same_gaps() {
+    assert_eq!(gap_sequence(42, 10), gap_sequence(42, 10));
+}
+
+#[test]
+fn different_seeds_spawn_different_gaps() {
+    assert_ne!(gap_sequence(1, 10), gap_sequence(2, 10));
+}
@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use flappybirb::config::Config;
+use flappybirb::replay::{Replay, ReplayError};
+use flappybirb::world::bird::BirdColor;
+use flappybirb::world::CollisionMode;
+use flappybirb::{GameWorld, PlayState, SIMULATION_STEP, WORLD_SIZE};
+
+/// Play a run with `seed` that flaps whenever the bird sinks below the next opening
+fn play(seed: u64) -> GameWorld {
+    let mut world = GameWorld::new(WORLD_SIZE, seed, Config::default());
+    world.collision_mode = CollisionMode::Pixel;
+    world.bird.color = BirdColor::Red;
+    world.start(seed);
+    world.flap();
+
+    while !matches!(world.state.state, PlayState::GameOver) && world.state.tick < 120 * 60 {
+        let bird = world.state.bird_bounding_box;
+        let next = world.pipes.pairs().find(|pair| {
+            let [top, _] = pair.bounding_boxes_at(pair.offset(), WORLD_SIZE);
+            top.position.x + top.size.x > bird.position.x
+        });
+        if let Some(pair) = next {
+            let [_, bottom] = pair.bounding_boxes_at(pair.offset(), WORLD_SIZE);
+            if bird.position.y + bird.size.y > bottom.position.y - 30.0
+                && world.bird.y_velocity > 0.0
+            {
+                world.flap();
+            }
+        }
+        world.step(SIMULATION_STEP);
+    }
+    world
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("flappybirb-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join("replay.toml")
+}
+
+#[test]
+fn recorded_run_replays_the_same() {
+    let world = play(11);
+    assert!(matches!(world.state.state, PlayState::GameOver));
+    assert!(world.state.score > 0);
+
+    let path = temp_path("replay");
+    Replay::capture(&world).save(&path).unwrap();
+    let replay = Replay::load(&path).unwrap();
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+    assert_eq!(replay.bird_color, BirdColor::Red);
+    let outcome = replay.run();
+    assert!(replay.matches(&outcome), "{outcome:?}");
+    assert_eq!(outcome.score, world.state.score);
+}
+
+#[test]
+fn tampered_inputs_desync() {
+    let mut replay = Replay::capture(&play(11));
+
+    let mut late = replay.clone();
+    for flap in late.flaps.iter_mut().skip(1) {
+        *flap += 10;
+    }
+    assert!(!late.matches(&late.run()));
+
+    replay.flaps.remove(0);
+    assert!(!replay.matches(&replay.run()));
+}
+
+#[test]
+fn changes_during_a_run_are_not_recorded() {
+    let mut world = GameWorld::new(WORLD_SIZE, 3, Config::default());
+    world.bird.gravity = 1234.0;
+    world.start(3);
+    world.flap();
+    world.step(SIMULATION_STEP);
+    world.bird.gravity = 99.0;
+    world.bird.color = BirdColor::Yellow;
+
+    let replay = Replay::capture(&world);
+    assert_eq!(replay.config.bird.gravity, 1234.0);
+    assert_eq!(replay.bird_color, BirdColor::Blue);
+}
+
+#[test]
+fn invalid_config_is_rejected() {
+    let mut replay = Replay::capture(&play(11));
+    replay.config.pipes.aperture_percent = 5.0;
+
+    let path = temp_path("invalid-replay");
+    replay.save(&path).unwrap();
+    let result = Replay::load(&path);
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    assert!(matches!(result, Err(ReplayError::Config(_))), "{result:?}");
+}
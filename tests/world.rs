@@ -0,0 +1,160 @@
+use std::time::Duration;
+
+use flappybirb::config::Config;
+use flappybirb::{GameWorld, PlayState, WorldEvent, SIMULATION_STEP, WORLD_SIZE};
+
+fn world() -> GameWorld {
+    let mut world = GameWorld::new(WORLD_SIZE, 7, Config::default());
+    world.start(7);
+    world
+}
+
+#[test]
+fn flap_moves_the_bird_up() {
+    let mut world = world();
+    world.flap();
+    world.step(SIMULATION_STEP);
+    assert!(matches!(world.state.state, PlayState::Playing));
+    assert!(world.bird.y_velocity < 0.0);
+    assert_eq!(world.take_events(), [WorldEvent::Flapped]);
+
+    let start = world.bird.y_position;
+    world.step(SIMULATION_STEP);
+    assert!(world.bird.y_position < start);
+}
+
+#[test]
+fn hitting_the_ground_ends_the_run() {
+    let mut world = world();
+    // Pipes that never scroll in leave only the ground to hit
+    world.pipes.config.speed = 0.0;
+    world.flap();
+
+    let mut events = Vec::new();
+    for _ in 0..10_000 {
+        world.step(SIMULATION_STEP);
+        events.extend(world.take_events());
+        if matches!(world.state.state, PlayState::GameOver) {
+            break;
+        }
+    }
+
+    // The bird starts dying on the hit and the run is over once it rests on the ground
+    assert!(matches!(world.state.state, PlayState::GameOver));
+    assert!(world.bird.is_on_ground(&world.state));
+    let outcome: Vec<_> = events
+        .into_iter()
+        .filter(|event| *event != WorldEvent::Flapped)
+        .collect();
+    assert_eq!(outcome, [WorldEvent::Hit, WorldEvent::GameOver]);
+    // Hit and game over come in the same step, the flash still has to start
+    assert_eq!(world.state.time_since_hit, Some(Duration::ZERO));
+}
+
+#[test]
+fn dying_falls_to_the_ground_before_game_over() {
+    let mut world = world();
+    world.flap();
+
+    // Falling after a single flap runs into the first pipe, well above the ground
+    for _ in 0..10_000 {
+        world.step(SIMULATION_STEP);
+        if world.take_events().contains(&WorldEvent::Hit) {
+            break;
+        }
+    }
+    assert!(matches!(world.state.state, PlayState::Dying));
+    assert!(!world.bird.is_on_ground(&world.state));
+
+    for _ in 0..10_000 {
+        world.step(SIMULATION_STEP);
+        if !matches!(world.state.state, PlayState::Dying) {
+            break;
+        }
+    }
+    assert!(matches!(world.state.state, PlayState::GameOver));
+    assert!(world.bird.is_on_ground(&world.state));
+    assert_eq!(world.take_events(), [WorldEvent::GameOver]);
+}
+
+#[test]
+fn passing_a_pair_scores_once() {
+    let mut world = world();
+    world.hit_detection = false;
+
+    let mut scored_at = Vec::new();
+    for tick in 0..10_000 {
+        if tick % 20 == 0 {
+            world.flap();
+        }
+        world.step(SIMULATION_STEP);
+        let scored = world
+            .take_events()
+            .into_iter()
+            .filter(|event| *event == WorldEvent::Scored)
+            .count();
+        assert!(scored <= 1, "scored {scored} times in one step");
+        if scored == 1 {
+            scored_at.push(tick);
+        }
+        if world.state.score == 3 {
+            break;
+        }
+    }
+
+    assert_eq!(world.state.score, 3);
+    assert_eq!(scored_at.len(), 3);
+}
+
+/// Bird height, pipe offsets and tick, which all change with every step of a run
+fn snapshot(world: &GameWorld) -> (f32, Vec<f32>, u64) {
+    let offsets = world.pipes.pairs().map(|pair| pair.offset()).collect();
+    (world.bird.y_position, offsets, world.state.tick)
+}
+
+#[test]
+fn paused_world_stands_still_until_resumed() {
+    let mut world = world();
+    world.flap();
+    for _ in 0..60 {
+        world.step(SIMULATION_STEP);
+    }
+    let before = snapshot(&world);
+    assert!(!before.1.is_empty());
+    let time_in_state = world.state.time_in_state;
+
+    world.toggle_pause();
+    assert!(matches!(world.state.state, PlayState::Paused));
+    assert!(matches!(world.unpaused_state(), PlayState::Playing));
+    for _ in 0..60 {
+        world.step(SIMULATION_STEP);
+    }
+    assert_eq!(snapshot(&world), before);
+
+    world.toggle_pause();
+    assert!(matches!(world.state.state, PlayState::Playing));
+    assert_eq!(world.state.time_in_state, time_in_state);
+    world.step(SIMULATION_STEP);
+    assert_ne!(snapshot(&world), before);
+}
+
+#[test]
+fn pausing_needs_a_run_in_progress() {
+    let mut world = GameWorld::new(WORLD_SIZE, 7, Config::default());
+    world.pause();
+    assert!(matches!(world.state.state, PlayState::MainMenu));
+
+    world.start(7);
+    world.pipes.config.speed = 0.0;
+    world.flap();
+    for _ in 0..10_000 {
+        world.step(SIMULATION_STEP);
+        if matches!(world.state.state, PlayState::GameOver) {
+            break;
+        }
+    }
+    assert!(matches!(world.state.state, PlayState::GameOver));
+    world.pause();
+    assert!(matches!(world.state.state, PlayState::GameOver));
+    assert!(matches!(world.unpaused_state(), PlayState::GameOver));
+}